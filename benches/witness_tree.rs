@@ -0,0 +1,69 @@
+use block_verkle_proof_extractor::keyvals::KeyVals;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use verkle_tree_img::verkle::WitnessTree;
+use verkle_trie::{database::memory_db::MemoryDb, proof::UpdateHint, TestConfig, Trie, TrieTrait};
+
+// Pseudo-random keys, every fourth one shares the stem of the previous key
+fn keys(count: usize) -> Vec<[u8; 32]> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut keys: Vec<[u8; 32]> = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut key = [0u8; 32];
+        match keys.last() {
+            Some(last) if i % 4 == 0 => key[..31].copy_from_slice(&last[..31]),
+            _ => {
+                for chunk in key[..31].chunks_mut(8) {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    chunk.copy_from_slice(&state.to_le_bytes()[..chunk.len()]);
+                }
+            }
+        }
+        key[31] = i as u8;
+        keys.push(key);
+    }
+    keys
+}
+
+// Witness of `count` keys, a tenth of them missing from the trie
+fn witness(count: usize) -> (UpdateHint, KeyVals) {
+    let keys = keys(count);
+    let mut trie = Trie::new(TestConfig::new(MemoryDb::new()));
+    for key in keys.iter().skip(count / 10) {
+        trie.insert_single(*key, [1u8; 32]);
+    }
+
+    let values: Vec<_> = keys.iter().map(|key| trie.get(*key)).collect();
+    let proof = trie.create_verkle_proof(keys.clone().into_iter());
+    let (_, hint) = proof.check(keys.clone(), values.clone(), trie.root_commitment());
+
+    (hint.expect("update hint"), KeyVals { keys, values })
+}
+
+fn bench_from_hint(c: &mut Criterion) {
+    let mut group = c.benchmark_group("witness_tree");
+    group.sample_size(20);
+
+    for count in [1_000, 10_000, 20_000] {
+        let (hint, keyvals) = witness(count);
+        group.bench_with_input(BenchmarkId::new("from_hint", count), &count, |b, _| {
+            b.iter(|| WitnessTree::from_hint(&hint, &keyvals))
+        });
+
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        group.bench_with_input(BenchmarkId::new("find_key", count), &count, |b, _| {
+            b.iter(|| {
+                keyvals
+                    .keys
+                    .iter()
+                    .filter_map(|key| tree.find_key(key))
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_from_hint);
+criterion_main!(benches);
@@ -1,10 +1,20 @@
-use clap::Parser;
+use crate::{
+    render::{text_tree, TextOptions},
+    tui::{self, App},
+    verkle::{decode_block, get_rlp},
+    witness::{verify_witness, BlockRlp, WitnessError},
+};
+use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
     #[clap(short, long, default_value = "config.toml")]
     pub config_path: std::path::PathBuf,
+    /// Runs the server if none is given
+    #[clap(subcommand)]
+    pub command: Option<Command>,
 }
 
 impl Default for Args {
@@ -12,3 +22,126 @@ impl Default for Args {
         Self::parse()
     }
 }
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Verifies the witness of a block and prints its tree
+    #[clap(alias = "verify")]
+    Inspect(InspectArgs),
+    /// Explores the witness of a block in the terminal
+    Tui(BlockSource),
+}
+
+/// The block to verify and its parent, from the node or from files
+#[derive(clap::Args)]
+pub struct BlockSource {
+    /// Block to fetch from the node along with its parent
+    #[clap(required_unless_present = "rlp", conflicts_with = "rlp")]
+    pub block_number: Option<u64>,
+    /// File with the RLP of the block, in hex or binary
+    #[clap(long)]
+    pub rlp: Option<PathBuf>,
+    /// File with the RLP of the parent block, fetched from the node if not given
+    #[clap(long, requires = "rlp")]
+    pub parent_rlp: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct InspectArgs {
+    #[clap(flatten)]
+    pub source: BlockSource,
+    /// Draw the branches with ASCII only
+    #[clap(long)]
+    pub ascii: bool,
+    /// Count the children of nodes at this depth instead of printing them
+    #[clap(long)]
+    pub max_depth: Option<usize>,
+    /// Hex digits kept of commitments, stems and values
+    #[clap(long, default_value = "8")]
+    pub hex_width: usize,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<(), anyhow::Error> {
+        match self {
+            Command::Inspect(args) => inspect(args).await,
+            Command::Tui(source) => {
+                let block = source.fetch().await?;
+                tokio::task::spawn_blocking(move || tui::run(App::load(block))).await?
+            }
+        }
+    }
+}
+
+// `0x` prefixed hex, as `debug_getBlockRlp` returns it
+fn read_rlp(path: &Path) -> Result<String, anyhow::Error> {
+    let content = std::fs::read(path)
+        .map_err(|err| anyhow::anyhow!("Failed to read {}: {err}", path.display()))?;
+    let text = String::from_utf8_lossy(&content);
+    let text = text.trim().trim_start_matches("0x");
+    match hex::decode(text) {
+        Ok(_) => Ok(format!("0x{text}")),
+        Err(_) => Ok(format!("0x{}", hex::encode(&content))),
+    }
+}
+
+impl BlockSource {
+    /// Block number and the RLPs of the block and its parent
+    pub(crate) async fn fetch(&self) -> Result<BlockRlp, anyhow::Error> {
+        let (number, rlp) = match (&self.rlp, self.block_number) {
+            (Some(path), _) => {
+                let rlp = read_rlp(path)?;
+                let number = decode_block(rlp.clone())
+                    .map_err(|err| anyhow::anyhow!("Failed to decode the block: {err}"))?
+                    .header
+                    .number
+                    .iter()
+                    .fold(0u64, |number, byte| number << 8 | *byte as u64);
+                (number, rlp)
+            }
+            (None, Some(number)) => (number, get_rlp(number).await?),
+            (None, None) => {
+                return Err(anyhow::anyhow!("Either a block number or --rlp is needed"))
+            }
+        };
+        let parent_rlp = match &self.parent_rlp {
+            Some(path) => read_rlp(path)?,
+            None if number > 0 => get_rlp(number - 1).await?,
+            None => return Err(anyhow::anyhow!("Block 0 has no parent to verify against")),
+        };
+        Ok(BlockRlp {
+            number,
+            rlp,
+            parent_rlp,
+        })
+    }
+}
+
+async fn inspect(args: InspectArgs) -> Result<(), anyhow::Error> {
+    let BlockRlp {
+        number: block_number,
+        rlp: block_rlp,
+        parent_rlp: previous_block_rlp,
+    } = args.source.fetch().await?;
+
+    let witness = verify_witness(block_rlp, previous_block_rlp).map_err(|err| match err {
+        WitnessError::Decode(err) => anyhow::anyhow!("Failed to decode the block: {err}"),
+        WitnessError::Verification(err) => {
+            anyhow::anyhow!("Block {block_number} failed verification: {err}")
+        }
+    })?;
+
+    println!(
+        "block {block_number}: verified, {} keys, {} stems, {} bytes of proof",
+        witness.keyvals.keys.len(),
+        witness.hint.depths_and_ext_by_stem.len(),
+        witness.proof_size
+    );
+    let options = TextOptions {
+        ascii: args.ascii,
+        max_depth: args.max_depth,
+        hex_width: args.hex_width,
+    };
+    print!("{}", text_tree(&witness.tree, &options));
+    Ok(())
+}
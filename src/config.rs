@@ -1,3 +1,7 @@
+use crate::{
+    render::RenderConfig,
+    verkle::{GasSchedule, MptModel},
+};
 use config::{Config as LibConfig, File};
 use serde::Deserialize;
 use std::{net::SocketAddr, path::PathBuf, str::FromStr};
@@ -6,18 +10,24 @@ use std::{net::SocketAddr, path::PathBuf, str::FromStr};
 #[serde(default)]
 pub struct Config {
     pub server: ServerConfiguration,
+    pub gas: GasSchedule,
+    pub mpt: MptModel,
+    pub render: RenderConfig,
 }
 
 #[derive(Deserialize, Clone)]
 #[serde(default)]
 pub struct ServerConfiguration {
     pub addr: SocketAddr,
+    /// Maximum number of blocks in range requests
+    pub max_block_range: u64,
 }
 
 impl Default for ServerConfiguration {
     fn default() -> Self {
         Self {
             addr: SocketAddr::from_str("0.0.0.0:8043").expect("should be valid url"),
+            max_block_range: 100,
         }
     }
 }
@@ -30,9 +40,15 @@ impl Config {
             builder = builder.add_source(File::from(file));
         }
 
-        builder
+        let config: Self = builder
             .build()
             .expect("Failed to build config")
-            .try_deserialize()
+            .try_deserialize()?;
+        config
+            .mpt
+            .validate()
+            .map_err(|err| config::ConfigError::Message(format!("[mpt] {err}")))?;
+
+        Ok(config)
     }
 }
@@ -0,0 +1,56 @@
+use crate::{
+    dot::{
+        heat_color,
+        merge::{merge_trees, Merged},
+        Graph,
+    },
+    verkle::WitnessTree,
+};
+
+/// Witness trees of a block range merged into one. Every node is labelled with the number
+/// of blocks touching it and colored from green (few blocks) to red (every block).
+pub(crate) fn aggregate_graph(trees: &[&WitnessTree]) -> Graph {
+    let Merged { mut graph, sources } = merge_trees("aggregate", trees);
+
+    for (n, sources) in sources.iter().enumerate() {
+        let blocks = sources.len();
+        graph.nodes[n].0 = format!("{}\n{blocks} of {} blocks", graph.nodes[n].0, trees.len());
+        graph
+            .node_colors
+            .insert(n, heat_color(blocks as u64, trees.len() as u64));
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::witness;
+
+    #[test]
+    fn test_aggregate_counts_blocks() {
+        let (hot, cold) = ([0u8; 32], [1u8; 32]);
+        let trees: Vec<_> = [vec![hot], vec![hot, cold], vec![hot]]
+            .iter()
+            .map(|keys| {
+                let (hint, keyvals) = witness(keys, &[]);
+                WitnessTree::from_hint(&hint, &keyvals)
+            })
+            .collect();
+        let trees: Vec<_> = trees.iter().collect();
+
+        let graph = aggregate_graph(&trees);
+
+        let leaf = |key: [u8; 32]| {
+            graph
+                .attrs
+                .iter()
+                .position(|attrs| attrs.key == Some(key))
+                .unwrap()
+        };
+        assert!(graph.nodes[leaf(hot)].0.ends_with("\n3 of 3 blocks"));
+        assert!(graph.nodes[leaf(cold)].0.ends_with("\n1 of 3 blocks"));
+        assert_eq!(graph.node_colors[&leaf(hot)], heat_color(3, 3));
+        assert_eq!(graph.node_colors[&0], heat_color(3, 3));
+    }
+}
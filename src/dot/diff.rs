@@ -0,0 +1,97 @@
+use crate::{
+    dot::{
+        from_hint::node_label,
+        merge::{merge_trees, Merged},
+        Graph,
+    },
+    verkle::{NodeKind, WitnessTree},
+};
+
+/// Which of the two witnesses a node of the diff comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffSide {
+    OnlyA,
+    OnlyB,
+    Both,
+    /// Leaf of both witnesses with different values
+    Changed,
+}
+
+impl DiffSide {
+    /// Fill color, nodes of both witnesses keep the color of their style
+    pub fn color(self) -> Option<&'static str> {
+        match self {
+            DiffSide::OnlyA => Some("#ef8a62"),
+            DiffSide::OnlyB => Some("#67a9cf"),
+            DiffSide::Changed => Some("#fee08b"),
+            DiffSide::Both => None,
+        }
+    }
+}
+
+fn value_in(tree: &WitnessTree, key: &[u8; 32]) -> Option<[u8; 32]> {
+    match tree.find_key(key).map(|id| &tree.node(id).kind) {
+        Some(NodeKind::Leaf { value, .. }) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Union of the witness trees of blocks A and B, colored by the block they come from.
+/// Nodes of both blocks are labelled as in B, changed leaves show both values.
+pub(crate) fn diff_graph(a: &WitnessTree, b: &WitnessTree) -> Graph {
+    let Merged { mut graph, sources } = merge_trees("diff", &[a, b]);
+
+    for (n, sources) in sources.iter().enumerate() {
+        let side = match sources[..] {
+            [0] => DiffSide::OnlyA,
+            [1] => DiffSide::OnlyB,
+            _ => match graph.attrs[n].key {
+                Some(key) if value_in(a, &key) != graph.attrs[n].value => {
+                    let old = a.find_key(&key).map(|id| node_label(a, id));
+                    graph.nodes[n].0 =
+                        format!("{}\n-> {}", old.unwrap_or_default(), graph.nodes[n].0);
+                    DiffSide::Changed
+                }
+                _ => DiffSide::Both,
+            },
+        };
+        if let Some(color) = side.color() {
+            graph.node_colors.insert(n, color.to_owned());
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::witness;
+
+    #[test]
+    fn test_diff_sides() {
+        let (only_a, shared, only_b) = ([0u8; 32], [1u8; 32], [2u8; 32]);
+        let (hint, keyvals) = witness(&[only_a, shared], &[]);
+        let a = WitnessTree::from_hint(&hint, &keyvals);
+        let (hint, mut keyvals) = witness(&[shared, only_b], &[]);
+        keyvals.values[0] = Some([2u8; 32]);
+        let b = WitnessTree::from_hint(&hint, &keyvals);
+
+        let graph = diff_graph(&a, &b);
+
+        let color = |key: [u8; 32]| {
+            let n = graph
+                .attrs
+                .iter()
+                .position(|attrs| attrs.key == Some(key))
+                .unwrap();
+            graph.node_colors.get(&n).cloned()
+        };
+        let side = |side: DiffSide| side.color().map(str::to_owned);
+        assert_eq!(color(only_a), side(DiffSide::OnlyA));
+        assert_eq!(color(only_b), side(DiffSide::OnlyB));
+        assert_eq!(color(shared), side(DiffSide::Changed));
+        // the root is shared
+        assert_eq!(graph.node_colors.get(&0), None);
+        assert_eq!(graph.edges.len(), graph.nodes.len() - 1);
+    }
+}
@@ -1,173 +1,356 @@
-use ark_serialize::CanonicalSerialize;
-use block_verkle_proof_extractor::keyvals::KeyVals;
-use std::{borrow::Cow, io::Write, path::PathBuf};
-use verkle_trie::proof::UpdateHint;
-
-type Node = usize;
-type Edge<'a> = &'a (usize, usize, u8);
-struct Graph {
-    nodes: Vec<(String, Option<String>)>,
-    edges: Vec<(usize, usize, u8)>,
+use crate::{
+    dot::{Graph, Node, NodeAttrs, NodeStyle},
+    verkle::{commitment_to_hex, header_stem, NodeId, NodeKind, TreeNode, WitnessTree},
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Leaves whose paths from the root are emphasized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    Key([u8; 32]),
+    Stem([u8; 31]),
+    /// The header stem of an account, resolved when parsed
+    Address {
+        address: [u8; 20],
+        stem: [u8; 31],
+    },
 }
 
-pub fn to_dot(
-    uh: &UpdateHint,
-    keyvals: &KeyVals,
-    file_path: &PathBuf,
-) -> Result<(), anyhow::Error> {
-    use std::fs::File;
-    let mut f = File::create(file_path)?;
+impl Highlight {
+    pub fn parse(value: &str) -> Result<Self, anyhow::Error> {
+        let bytes = hex::decode(value.trim_start_matches("0x"))?;
+        match bytes.len() {
+            32 => Ok(Highlight::Key(bytes.try_into().unwrap())),
+            31 => Ok(Highlight::Stem(bytes.try_into().unwrap())),
+            20 => {
+                let address = bytes.try_into().unwrap();
+                let stem = header_stem(&address);
+                Ok(Highlight::Address { address, stem })
+            }
+            len => Err(anyhow::anyhow!(
+                "Expected a 32 bytes key, a 31 bytes stem or a 20 bytes address, got {len} bytes"
+            )),
+        }
+    }
+
+    fn matches(&self, key: &[u8]) -> bool {
+        match self {
+            Highlight::Key(k) => key == k,
+            Highlight::Stem(stem) | Highlight::Address { stem, .. } => key.starts_with(stem),
+        }
+    }
+
+    fn matches_stem(&self, stem: &[u8]) -> bool {
+        match self {
+            Highlight::Key(k) => k.starts_with(stem),
+            Highlight::Stem(s) | Highlight::Address { stem: s, .. } => stem == s,
+        }
+    }
 
-    render_to(&mut f, uh, keyvals)
+    // Whether the matching stems lie below the tree path
+    fn matches_path(&self, path: &[u8]) -> bool {
+        match self {
+            Highlight::Key(k) => k.starts_with(path),
+            Highlight::Stem(s) | Highlight::Address { stem: s, .. } => s.starts_with(path),
+        }
+    }
 }
 
-fn common_prefix(v1: &[u8], v2: &[u8]) -> usize {
-    v1.iter().zip(v2).filter(|&(x, y)| x == y).count()
+#[derive(Default)]
+pub struct RenderOptions {
+    /// Fill colors of extension nodes by their stem
+    pub stem_colors: BTreeMap<[u8; 31], String>,
+    pub highlight: Option<Highlight>,
+    /// Add a legend of the node styles
+    pub legend: bool,
 }
 
-// We are transforming sorted Vec<paths, commitments> to the prefix-tree (dot)
-pub fn render_to<W: Write>(
-    output: &mut W,
-    data: &UpdateHint,
-    keyvals: &KeyVals,
-) -> Result<(), anyhow::Error> {
-    let mut nodes = vec![];
-    let mut previous_items = Vec::<(Vec<u8>, String)>::new();
-    let mut edges = vec![];
+/// Maps `value` in `0..=max` to a color from green (cheap) to red (hot)
+pub fn heat_color(value: u64, max: u64) -> String {
+    let ratio = match max {
+        0 => 0.0,
+        _ => value as f64 / max as f64,
+    };
+    let red = (255.0 * ratio.min(1.0)) as u8;
+    let green = (255.0 * (1.0 - ratio.min(1.0))) as u8;
 
-    for (my_index, (path, comm)) in data.commitments_by_path.iter().enumerate() {
-        let mut v_tmp = vec![];
-        let _res = CanonicalSerialize::serialize(comm, &mut v_tmp);
-        let commitment_in_hex = format!("0x{}", hex::encode(v_tmp.clone()));
-        // firstly, we writing a common commitments (branch nodes)
-        nodes.push((commitment_in_hex.clone(), None));
+    format!("#{red:02x}{green:02x}40")
+}
 
-        // getting path info for edges
-        for (index, item) in previous_items.iter().enumerate() {
-            let len_current = item.0.len();
+// Label of a tree node, commitments are shown in full
+pub(crate) fn node_label(tree: &WitnessTree, id: NodeId) -> String {
+    let node = tree.node(id);
+    match &node.kind {
+        NodeKind::Internal | NodeKind::Extension { .. } | NodeKind::OtherStem { .. } => {
+            match &node.commitment {
+                Some(comm) => commitment_to_hex(comm),
+                None => "".to_owned(),
+            }
+        }
+        NodeKind::Suffix { index } => {
+            let comm = match &node.commitment {
+                Some(comm) => format!("{}\n", commitment_to_hex(comm)),
+                None => "".to_owned(),
+            };
+            format!("{comm}C{index}")
+        }
+        NodeKind::Empty => "empty".to_owned(),
+        NodeKind::Leaf { value, .. } => {
+            let mut value = hex::encode(value);
+            if value == "0000000000000000000000000000000000000000000000000000000000000000" {
+                value = "00..".to_owned();
+            }
+            format!("0x{value}")
+        }
+        NodeKind::Absent { key } => match node.parent.map(|parent| &tree.node(parent).kind) {
+            Some(NodeKind::Suffix { .. }) => "absent".to_owned(),
+            // the requested key of a stem that is not in the tree
+            _ => format!("absent\n0x{}", hex::encode(key)),
+        },
+    }
+}
 
-            if path.len() == len_current + 1 {
-                let common = common_prefix(path, &item.0);
+// Stem printed under the label of extension nodes
+pub(crate) fn node_stem(node: &TreeNode) -> Option<String> {
+    match &node.kind {
+        NodeKind::Extension { stem } | NodeKind::OtherStem { stem } => Some(hex::encode(stem)),
+        _ => None,
+    }
+}
 
-                if common == len_current {
-                    edges.push((index, my_index, path[path.len() - 1]));
-                }
+pub(crate) fn node_attrs(tree: &WitnessTree, id: NodeId, depth: usize) -> NodeAttrs {
+    let node = tree.node(id);
+    let (key, value) = match &node.kind {
+        NodeKind::Leaf { key, value } => (Some(*key), Some(*value)),
+        NodeKind::Absent { key } => (Some(*key), None),
+        _ => (None, None),
+    };
+    let stem = match &node.kind {
+        NodeKind::Extension { stem } | NodeKind::OtherStem { stem } => Some(*stem),
+        // C1/C2 hang off the extension of their stem
+        NodeKind::Suffix { .. } => node
+            .parent
+            .and_then(|parent| match &tree.node(parent).kind {
+                NodeKind::Extension { stem } => Some(*stem),
+                _ => None,
+            }),
+        _ => key.map(|key| key[..31].try_into().unwrap()),
+    };
+    NodeAttrs {
+        depth,
+        path: node.path.clone(),
+        commitment: node.commitment.as_ref().map(commitment_to_hex),
+        stem,
+        key,
+        value,
+    }
+}
+
+// We are transforming the witness tree to the graph (dot)
+pub(crate) fn build_graph(tree: &WitnessTree, options: &RenderOptions) -> Graph {
+    let mut nodes = vec![];
+    let mut styles = vec![];
+    let mut attrs: Vec<NodeAttrs> = vec![];
+    let mut node_colors = HashMap::new();
+    let mut matched = vec![];
+    let mut edges = vec![];
+    // graph node of every drawn tree node
+    let mut drawn = HashMap::<NodeId, Node>::new();
+
+    // tree nodes are created top-down, so parents are drawn before their children
+    for (id, node) in tree.nodes().iter().enumerate() {
+        let index = nodes.len();
+        if let NodeKind::Extension { stem } = &node.kind {
+            if let Some(color) = options.stem_colors.get(stem) {
+                node_colors.insert(index, color.clone());
             }
         }
-        previous_items.push((path.clone(), commitment_in_hex));
-    }
+        let is_match = options.highlight.is_some_and(|h| match &node.kind {
+            NodeKind::Extension { stem } | NodeKind::OtherStem { stem } => h.matches_stem(stem),
+            NodeKind::Empty => h.matches_path(&node.path),
+            NodeKind::Leaf { key, .. } | NodeKind::Absent { key } => h.matches(key),
+            _ => false,
+        });
+        if is_match {
+            matched.push(index);
+        }
 
-    // get extention nodes
-    for (comm, val2) in data.depths_and_ext_by_stem.iter() {
-        match val2.0 {
-            // we are not intrested in POST-state keyvals
-            verkle_trie::proof::ExtPresent::None => continue,
-            // we don't use this nodes too
-            verkle_trie::proof::ExtPresent::DifferentStem => continue,
-            // ok
-            verkle_trie::proof::ExtPresent::Present => {}
-        };
+        let parent = node.parent.and_then(|parent| drawn.get(&parent)).copied();
+        let depth = parent.map_or(0, |parent| attrs[parent].depth + 1);
+        attrs.push(node_attrs(tree, id, depth));
+        nodes.push((node_label(tree, id), node_stem(node)));
+        styles.push(NodeStyle::of(tree, id));
+        drawn.insert(id, index);
 
-        for item in previous_items.iter() {
-            if item.0.len() == val2.1 as usize {
-                let common = common_prefix(comm, &item.0);
-
-                if common == val2.1 as usize {
-                    // find element in nodes and update it
-                    let index_element = nodes.iter().position(|x| x.0 == item.1);
-
-                    match index_element {
-                        Some(val) => nodes[val].1 = Some(hex::encode(comm)),
-                        // we can't get here
-                        None => {
-                            tracing::error!("How could you get here?");
-                            continue;
-                        }
-                    };
-
-                    let prefix = comm.clone().to_vec();
-
-                    // we need extention and len must be len 31
-                    // iterate throwght keys and get them
-                    for (indx, key) in keyvals.keys.iter().enumerate() {
-                        if keyvals.values[indx].is_none() {
-                            continue;
-                        }
-
-                        let common = common_prefix(&prefix, key);
-
-                        if common == 31 {
-                            let mut value = hex::encode(keyvals.values[indx].unwrap());
-                            if value == "0000000000000000000000000000000000000000000000000000000000000000" {
-                                value = "00..".to_owned();
-                            }
-
-                            nodes.push((format!("0x{value}"), None));
-                            edges.push((
-                                index_element.unwrap() + 1_usize,
-                                nodes.len() - 1,
-                                key[31],
-                            ));
-                        }
-                    }
+        if let Some(parent) = parent {
+            edges.push((parent, index, node.edge_label()));
+        }
+    }
+
+    // walk up from the matched leaves to the root
+    let highlighted = options.highlight.map(|_| {
+        let parents: HashMap<Node, Node> = edges.iter().map(|e| (e.1, e.0)).collect();
+        let mut highlighted = HashSet::new();
+        for mut node in matched {
+            while highlighted.insert(node) {
+                match parents.get(&node) {
+                    Some(parent) => node = *parent,
+                    None => break,
                 }
             }
         }
-    }
+        highlighted
+    });
 
-    let graph = Graph { nodes, edges };
+    // one unconnected node per style in use
+    let legend = nodes.len();
+    if options.legend {
+        let mut used = styles.clone();
+        used.sort();
+        used.dedup();
+        for style in used {
+            nodes.push((style.description().to_owned(), None));
+            styles.push(style);
+            attrs.push(NodeAttrs::default());
+        }
+    }
 
-    match dot::render(&graph, output) {
-        Ok(()) => Ok(()),
-        Err(err) => Err(anyhow::anyhow!("Error with render dot {err}")),
+    Graph {
+        nodes,
+        styles,
+        attrs,
+        legend,
+        edges,
+        node_colors,
+        highlighted,
+        ..Graph::new("witness")
     }
 }
 
-impl<'a> dot::Labeller<'a, Node, Edge<'a>> for Graph {
-    fn graph_id(&'a self) -> dot::Id<'a> {
-        dot::Id::new("example").unwrap()
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::{keys, witness};
+    use proptest::{prelude::*, sample::Index};
 
-    fn node_id(&'a self, n: &Node) -> dot::Id<'a> {
-        dot::Id::new(format!("N{}", n)).unwrap()
+    #[test]
+    fn test_absence_proofs_drawn() {
+        let mut other = [0u8; 32];
+        other[1] = 1;
+        let mut missing_suffix = [0u8; 32];
+        missing_suffix[31] = 9;
+        let (hint, keyvals) = witness(&[[0u8; 32]], &[other, missing_suffix, [5u8; 32]]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+
+        let graph = build_graph(&tree, &RenderOptions::default());
+
+        let labels: Vec<_> = graph.nodes.iter().map(|node| node.0.as_str()).collect();
+        assert!(labels.contains(&"empty"));
+        assert!(labels.contains(&"absent"));
+        let requested = format!("absent\n0x{}", hex::encode(other));
+        let requested = labels.iter().position(|l| *l == requested).unwrap();
+        // the requested key hangs off the stem found in its place
+        let (found, _, _) = graph.edges.iter().find(|e| e.1 == requested).unwrap();
+        assert_eq!(graph.nodes[*found].1, Some(hex::encode([0u8; 31])));
     }
 
-    fn node_label<'b>(&'b self, n: &Node) -> dot::LabelText<'b> {
-        let node = self.nodes[*n].clone();
-        let comm = node.0;
-        let ext = match node.1 {
-            Some(val) => format!("\next: 0x{}", val),
-            None => "".to_owned(),
+    #[test]
+    fn test_legend_lists_used_styles() {
+        let (hint, keyvals) = witness(&[[0u8; 32]], &[[5u8; 32]]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let options = RenderOptions {
+            legend: true,
+            ..RenderOptions::default()
         };
 
-        dot::LabelText::LabelStr(Cow::Owned(format!("{comm}{ext}")))
+        let graph = build_graph(&tree, &options);
+
+        let legend: Vec<_> = graph.styles[graph.legend..].to_vec();
+        assert_eq!(
+            legend,
+            vec![
+                NodeStyle::Root,
+                NodeStyle::Extension,
+                NodeStyle::Suffix,
+                NodeStyle::Leaf,
+                NodeStyle::Empty
+            ]
+        );
+        assert!(graph.edges.iter().all(|e| e.1 < graph.legend));
     }
 
-    fn edge_label<'b>(&'b self, edge: &Edge) -> dot::LabelText<'b> {
-        let symbol = match edge.2 {
-            1 => "c_1".to_owned(),
-            2 => "c_2".to_owned(),
-            _ => format!("{:0x}", edge.2),
+    #[test]
+    fn test_highlight_address() {
+        let address = "0x0102030000000000000000000000000000000000";
+        let highlight = Highlight::parse(address).unwrap();
+        let stem = match highlight {
+            Highlight::Address { stem, .. } => stem,
+            _ => panic!("parsed as {highlight:?}"),
+        };
+        assert_eq!(
+            hex::encode(stem),
+            "cac9a3e8dd152c9b5f8abcd254f1abe57d4acde35cfe0f919b43e6f0930714"
+        );
+        let mut balance = [0u8; 32];
+        balance[..31].copy_from_slice(&stem);
+        balance[31] = 1;
+        let (hint, keyvals) = witness(&[balance, [5u8; 32]], &[]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let options = RenderOptions {
+            highlight: Some(highlight),
+            ..RenderOptions::default()
         };
 
-        dot::LabelText::LabelStr(Cow::Owned(symbol))
-    }
-}
+        let graph = build_graph(&tree, &options);
 
-impl<'a> dot::GraphWalk<'a, Node, Edge<'a>> for Graph {
-    fn nodes(&self) -> dot::Nodes<'a, Node> {
-        (0..self.nodes.len()).collect()
+        // root, extension, C1 and the balance leaf
+        let highlighted = graph.highlighted.unwrap();
+        assert_eq!(highlighted.len(), 4);
+        let stems: Vec<_> = highlighted
+            .iter()
+            .filter_map(|n| graph.nodes[*n].1.clone())
+            .collect();
+        assert_eq!(stems, vec![hex::encode(stem)]);
     }
 
-    fn edges(&'a self) -> dot::Edges<'a, Edge<'a>> {
-        self.edges.iter().collect()
-    }
+    proptest! {
+        #[test]
+        fn prop_graph_is_tree(keys in keys(), split in any::<Index>()) {
+            let (present, absent) = keys.split_at(split.index(keys.len()) + 1);
+            let (hint, keyvals) = witness(present, absent);
+            let tree = WitnessTree::from_hint(&hint, &keyvals);
 
-    fn source(&self, e: &Edge) -> Node {
-        e.0
-    }
+            let graph = build_graph(&tree, &RenderOptions::default());
+
+            // every node but the root has exactly one parent
+            let mut parents = vec![0; graph.nodes.len()];
+            for (source, target, _) in graph.edges.iter() {
+                prop_assert!(source < target);
+                parents[*target] += 1;
+            }
+            prop_assert_eq!(parents[0], 0);
+            prop_assert!(parents[1..].iter().all(|count| *count == 1));
+            let ids: HashSet<_> = (0..graph.nodes.len()).map(|n| graph.node_id(n)).collect();
+            prop_assert_eq!(ids.len(), graph.nodes.len());
 
-    fn target(&self, e: &Edge) -> Node {
-        e.1
+            // leaves are attached to the suffix commitment of their extension
+            let value = format!("0x{}", hex::encode([1u8; 32]));
+            let parents: HashMap<Node, (Node, &str)> =
+                graph.edges.iter().map(|e| (e.1, (e.0, e.2.as_str()))).collect();
+            let mut leaves = 0;
+            for (suffix, target, label) in graph.edges.iter() {
+                if graph.nodes[*target].0 != value {
+                    continue;
+                }
+                let suffix_byte = u8::from_str_radix(label, 16).unwrap();
+                let (ext, slot) = parents[suffix];
+                prop_assert_eq!(slot, if suffix_byte < 128 { "c_1" } else { "c_2" });
+                let stem = graph.nodes[ext].1.as_ref().expect("suffix under extension");
+                let key = hex::decode(format!("{stem}{suffix_byte:02x}")).unwrap();
+                prop_assert!(present.iter().any(|k| k[..] == key[..]));
+                leaves += 1;
+            }
+            prop_assert_eq!(leaves, present.len());
+        }
     }
 }
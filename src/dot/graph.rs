@@ -0,0 +1,344 @@
+use crate::{dot::NodeStyle, render::Theme};
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::Write,
+};
+
+pub(crate) type Node = usize;
+type Edge<'a> = &'a (usize, usize, String);
+
+/// Tree data of a drawn node, empty for legend entries
+#[derive(Debug, Clone, Default)]
+pub struct NodeAttrs {
+    /// Number of edges from the root
+    pub depth: usize,
+    pub path: Vec<u8>,
+    pub commitment: Option<String>,
+    /// Stem the node belongs to: of an extension, its C1/C2 commitments and leaves
+    pub stem: Option<[u8; 31]>,
+    pub key: Option<[u8; 32]>,
+    pub value: Option<[u8; 32]>,
+}
+
+/// Drawable graph shared by the renderers
+pub(crate) struct Graph {
+    /// Derived from the blocks drawn
+    pub id: String,
+    /// What was drawn, e.g. the block number and its number of keys
+    pub metadata: BTreeMap<&'static str, String>,
+    /// Label and, for extension nodes, the stem in hex
+    pub nodes: Vec<(String, Option<String>)>,
+    pub styles: Vec<NodeStyle>,
+    pub attrs: Vec<NodeAttrs>,
+    /// Nodes from this index on are legend entries
+    pub legend: usize,
+    pub edges: Vec<(usize, usize, String)>,
+    pub node_colors: HashMap<Node, String>,
+    /// Nodes on the paths to highlighted leaves, None if nothing is highlighted
+    pub highlighted: Option<HashSet<Node>>,
+    /// Label lines longer than this many characters are cut
+    pub label_width: Option<usize>,
+    pub theme: Theme,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonNode {
+    pub id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stem: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: NodeStyle,
+    pub shape: &'static str,
+    pub color: String,
+    /// Graphviz style: filled, dashed, bold...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<&'static str>,
+    /// Entry of the legend rather than a tree node
+    pub legend: bool,
+    pub depth: usize,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// On the path to a highlighted leaf, absent if nothing is highlighted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlighted: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonEdge {
+    pub source: String,
+    pub target: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<&'static str>,
+}
+
+/// The graph with every style decision resolved, for clients drawing it themselves
+#[derive(Debug, Serialize)]
+pub struct JsonGraph {
+    pub id: String,
+    pub nodes: Vec<JsonNode>,
+    pub edges: Vec<JsonEdge>,
+}
+
+fn style_name(style: dot::Style) -> Option<&'static str> {
+    match style {
+        dot::Style::None => None,
+        style => Some(style.as_slice()),
+    }
+}
+
+impl Graph {
+    pub fn new(id: &str) -> Self {
+        Graph {
+            id: id.to_owned(),
+            metadata: BTreeMap::new(),
+            nodes: vec![],
+            styles: vec![],
+            attrs: vec![],
+            legend: 0,
+            edges: vec![],
+            node_colors: HashMap::new(),
+            highlighted: None,
+            label_width: None,
+            theme: Theme::Light,
+        }
+    }
+
+    /// Id of a node made of its type, tree path and stem or key, so that it stays the same
+    /// whatever the order of the nodes
+    pub fn node_id(&self, n: Node) -> String {
+        let style = self.styles[n];
+        if n >= self.legend {
+            return format!("legend_{}", style.name());
+        }
+        let prefix = match style {
+            NodeStyle::Root => "root",
+            NodeStyle::Internal => "internal",
+            NodeStyle::Extension => "ext",
+            NodeStyle::OtherStem => "other",
+            NodeStyle::Suffix => "suffix",
+            // a leaf keeps its id when its value is created or deleted
+            NodeStyle::Leaf | NodeStyle::ZeroLeaf | NodeStyle::Absent => "leaf",
+            NodeStyle::Empty => "empty",
+            NodeStyle::Summary => "more",
+        };
+        let attrs = &self.attrs[n];
+        let mut id = prefix.to_owned();
+        if !attrs.path.is_empty() {
+            id.push('_');
+            id.push_str(&hex::encode(&attrs.path));
+        }
+        if let Some(stem) = &self.nodes[n].1 {
+            id.push('_');
+            id.push_str(stem);
+        }
+        if let Some(key) = &attrs.key {
+            id.push('_');
+            id.push_str(&hex::encode(key));
+        }
+        id
+    }
+
+    pub fn label(&self, n: Node) -> String {
+        let (comm, stem) = &self.nodes[n];
+        let ext = match (stem, self.styles[n]) {
+            (Some(val), NodeStyle::OtherStem) => format!("\nother stem: 0x{}", val),
+            (Some(val), _) => format!("\next: 0x{}", val),
+            (None, _) => "".to_owned(),
+        };
+
+        let label = format!("{comm}{ext}");
+        match self.label_width {
+            Some(width) => label
+                .lines()
+                .map(|line| truncate(line, width))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => label,
+        }
+    }
+
+    pub fn style(&self, n: Node) -> dot::Style {
+        let style = if self.node_colors.contains_key(&n) {
+            dot::Style::Filled
+        } else {
+            match &self.highlighted {
+                Some(highlighted) if highlighted.contains(&n) => dot::Style::Bold,
+                _ => self.styles[n].style(),
+            }
+        };
+        // light text doesn't read on the pale fills, so the dark theme only outlines nodes
+        match (self.theme, style) {
+            (Theme::Dark, dot::Style::Filled) => dot::Style::Bold,
+            _ => style,
+        }
+    }
+
+    pub fn color(&self, n: Node) -> &str {
+        if let Some(color) = self.node_colors.get(&n) {
+            return color;
+        }
+        match &self.highlighted {
+            Some(highlighted) if n < self.legend => highlight_color(highlighted.contains(&n)),
+            _ => self.styles[n].color(),
+        }
+    }
+
+    pub fn edge_style(&self, e: &(usize, usize, String)) -> dot::Style {
+        match &self.highlighted {
+            Some(highlighted) if highlighted.contains(&e.1) => dot::Style::Bold,
+            // edges into proofs of absence and hidden nodes
+            _ => match self.styles[e.1] {
+                NodeStyle::Absent
+                | NodeStyle::OtherStem
+                | NodeStyle::Empty
+                | NodeStyle::Summary => dot::Style::Dashed,
+                _ => dot::Style::None,
+            },
+        }
+    }
+
+    pub fn edge_color(&self, e: &(usize, usize, String)) -> Option<&str> {
+        self.highlighted
+            .as_ref()
+            .map(|highlighted| highlight_color(highlighted.contains(&e.1)))
+    }
+
+    pub fn to_json(&self) -> JsonGraph {
+        let nodes = (0..self.nodes.len())
+            .map(|n| JsonNode {
+                id: self.node_id(n),
+                label: self.label(n),
+                stem: self.nodes[n].1.as_ref().map(|stem| format!("0x{stem}")),
+                kind: self.styles[n],
+                shape: self.styles[n].shape(),
+                color: self.color(n).to_owned(),
+                style: style_name(self.style(n)),
+                legend: n >= self.legend,
+                depth: self.attrs[n].depth,
+                path: format!("0x{}", hex::encode(&self.attrs[n].path)),
+                commitment: self.attrs[n].commitment.clone(),
+                key: self.attrs[n]
+                    .key
+                    .map(|key| format!("0x{}", hex::encode(key))),
+                value: self.attrs[n]
+                    .value
+                    .map(|value| format!("0x{}", hex::encode(value))),
+                highlighted: self
+                    .highlighted
+                    .as_ref()
+                    .map(|highlighted| highlighted.contains(&n)),
+            })
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .map(|edge| JsonEdge {
+                source: self.node_id(edge.0),
+                target: self.node_id(edge.1),
+                label: edge.2.clone(),
+                color: Graph::edge_color(self, edge).map(str::to_owned),
+                style: style_name(Graph::edge_style(self, edge)),
+            })
+            .collect();
+
+        JsonGraph {
+            id: self.id.clone(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Writes the graph in the DOT language
+    pub fn write_dot<W: Write>(&self, output: &mut W) -> Result<(), anyhow::Error> {
+        match dot::render(self, output) {
+            Ok(()) => Ok(()),
+            Err(err) => Err(anyhow::anyhow!("Error with render dot {err}")),
+        }
+    }
+}
+
+fn truncate(line: &str, width: usize) -> String {
+    match line.char_indices().nth(width) {
+        Some(_) => {
+            let kept: String = line.chars().take(width.saturating_sub(1)).collect();
+            format!("{kept}…")
+        }
+        None => line.to_owned(),
+    }
+}
+
+fn highlight_color(highlighted: bool) -> &'static str {
+    match highlighted {
+        true => "red",
+        false => "gray80",
+    }
+}
+
+impl<'a> dot::Labeller<'a, Node, Edge<'a>> for Graph {
+    fn graph_id(&'a self) -> dot::Id<'a> {
+        dot::Id::new(self.id.as_str()).unwrap()
+    }
+
+    fn node_id(&'a self, n: &Node) -> dot::Id<'a> {
+        dot::Id::new(self.node_id(*n)).unwrap()
+    }
+
+    fn node_label<'b>(&'b self, n: &Node) -> dot::LabelText<'b> {
+        dot::LabelText::LabelStr(Cow::Owned(self.label(*n)))
+    }
+
+    fn node_shape(&'a self, n: &Node) -> Option<dot::LabelText<'a>> {
+        Some(dot::LabelText::LabelStr(Cow::Borrowed(
+            self.styles[*n].shape(),
+        )))
+    }
+
+    fn node_style(&'a self, n: &Node) -> dot::Style {
+        self.style(*n)
+    }
+
+    fn node_color(&'a self, n: &Node) -> Option<dot::LabelText<'a>> {
+        Some(dot::LabelText::LabelStr(Cow::Borrowed(self.color(*n))))
+    }
+
+    fn edge_style(&'a self, e: &Edge) -> dot::Style {
+        Graph::edge_style(self, e)
+    }
+
+    fn edge_color(&'a self, e: &Edge) -> Option<dot::LabelText<'a>> {
+        Graph::edge_color(self, e).map(|color| dot::LabelText::LabelStr(Cow::Borrowed(color)))
+    }
+
+    fn edge_label<'b>(&'b self, edge: &Edge) -> dot::LabelText<'b> {
+        dot::LabelText::LabelStr(Cow::Owned(edge.2.clone()))
+    }
+}
+
+impl<'a> dot::GraphWalk<'a, Node, Edge<'a>> for Graph {
+    fn nodes(&self) -> dot::Nodes<'a, Node> {
+        (0..self.nodes.len()).collect()
+    }
+
+    fn edges(&'a self) -> dot::Edges<'a, Edge<'a>> {
+        self.edges.iter().collect()
+    }
+
+    fn source(&self, e: &Edge) -> Node {
+        e.0
+    }
+
+    fn target(&self, e: &Edge) -> Node {
+        e.1
+    }
+}
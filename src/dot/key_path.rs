@@ -0,0 +1,175 @@
+use crate::{
+    dot::{Graph, NodeAttrs, NodeStyle},
+    verkle::{CommitmentKind, ExtensionStatus, KeyProof},
+};
+
+// Draws the commitments from the root to a single key as a chain
+pub fn key_path_graph(proof: &KeyProof) -> Graph {
+    let mut graph = Graph::new("key_path");
+
+    for item in proof.commitments.iter() {
+        let (label, stem, style) = match item.kind {
+            CommitmentKind::Internal if graph.nodes.is_empty() => {
+                (item.commitment.clone(), None, NodeStyle::Root)
+            }
+            CommitmentKind::Internal => (item.commitment.clone(), None, NodeStyle::Internal),
+            CommitmentKind::Extension => match proof.other_stem {
+                Some(stem) => (
+                    item.commitment.clone(),
+                    Some(hex::encode(stem)),
+                    NodeStyle::OtherStem,
+                ),
+                None => (
+                    item.commitment.clone(),
+                    Some(hex::encode(proof.stem)),
+                    NodeStyle::Extension,
+                ),
+            },
+            CommitmentKind::Suffix => {
+                let suffix = item.path[item.path.len() - 1] - 1;
+                (
+                    format!("{}\nC{suffix}", item.commitment),
+                    None,
+                    NodeStyle::Suffix,
+                )
+            }
+        };
+        graph.attrs.push(NodeAttrs {
+            depth: graph.nodes.len(),
+            path: item.path.clone(),
+            commitment: Some(item.commitment.clone()),
+            ..NodeAttrs::default()
+        });
+        graph.nodes.push((label, stem));
+        graph.styles.push(style);
+
+        if graph.nodes.len() > 1 {
+            let symbol = match item.kind {
+                CommitmentKind::Suffix => format!("c_{}", item.path[item.path.len() - 1] - 1),
+                _ => format!("{:0x}", item.path[item.path.len() - 1]),
+            };
+            graph
+                .edges
+                .push((graph.nodes.len() - 2, graph.nodes.len() - 1, symbol));
+        }
+    }
+
+    let (last, style, symbol) = match proof.extension {
+        ExtensionStatus::Present => {
+            let (label, style) = match proof.value {
+                Some(value) if value == [0u8; 32] => {
+                    (format!("0x{}", hex::encode(value)), NodeStyle::ZeroLeaf)
+                }
+                Some(value) => (format!("0x{}", hex::encode(value)), NodeStyle::Leaf),
+                None => ("absent".to_owned(), NodeStyle::Absent),
+            };
+            (label, style, format!("{:0x}", proof.key[31]))
+        }
+        ExtensionStatus::DifferentStem => (
+            format!("absent\n0x{}", hex::encode(proof.key)),
+            NodeStyle::Absent,
+            "other stem".to_owned(),
+        ),
+        // an empty tree has its empty slot at the root, without an edge to it
+        ExtensionStatus::None => (
+            "empty".to_owned(),
+            NodeStyle::Empty,
+            match (proof.depth as usize).checked_sub(1) {
+                Some(slot) => format!("{:0x}", proof.stem[slot]),
+                None => String::new(),
+            },
+        ),
+    };
+    // tree path of the last node, as in `WitnessTree`
+    let mut path = match (proof.extension, proof.commitments.last()) {
+        (ExtensionStatus::Present, Some(suffix)) => suffix.path.clone(),
+        _ => proof.stem[..proof.depth as usize].to_vec(),
+    };
+    // empty slots prove the absence of the whole stem
+    let key = (proof.extension != ExtensionStatus::None).then_some(proof.key);
+    if key.is_some() {
+        path.push(proof.key[31]);
+    }
+    graph.attrs.push(NodeAttrs {
+        depth: graph.nodes.len(),
+        path,
+        key,
+        value: proof.value,
+        ..NodeAttrs::default()
+    });
+    graph.nodes.push((last, None));
+    graph.styles.push(style);
+    if graph.nodes.len() > 1 {
+        graph
+            .edges
+            .push((graph.nodes.len() - 2, graph.nodes.len() - 1, symbol));
+    }
+    graph.legend = graph.nodes.len();
+
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::{explain_key, testing::witness, WitnessTree};
+
+    fn edge_labels(graph: &Graph) -> Vec<&str> {
+        graph
+            .edges
+            .iter()
+            .map(|(_, _, label)| label.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_key_path_graph() {
+        let mut sibling = [0u8; 32];
+        sibling[0] = 1;
+        let mut other_stem = [0u8; 32];
+        other_stem[1] = 1;
+        let mut empty_slot = [0u8; 32];
+        empty_slot[0] = 2;
+        let (hint, keyvals) = witness(&[[0u8; 32], sibling], &[other_stem, empty_slot]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let graph = |key| key_path_graph(&explain_key(&tree, key).expect("key in witness"));
+
+        let present = graph(&[0u8; 32]);
+        assert_eq!(
+            present.styles,
+            vec![
+                NodeStyle::Root,
+                NodeStyle::Extension,
+                NodeStyle::Suffix,
+                NodeStyle::Leaf
+            ]
+        );
+        assert_eq!(edge_labels(&present), vec!["0", "c_1", "0"]);
+
+        let other = graph(&other_stem);
+        assert_eq!(
+            other.styles,
+            vec![NodeStyle::Root, NodeStyle::OtherStem, NodeStyle::Absent]
+        );
+        assert_eq!(edge_labels(&other), vec!["0", "other stem"]);
+        assert_eq!(other.nodes[1].1, Some(hex::encode([0u8; 31])));
+
+        let empty = graph(&empty_slot);
+        assert_eq!(empty.styles, vec![NodeStyle::Root, NodeStyle::Empty]);
+        assert_eq!(edge_labels(&empty), vec!["2"]);
+        assert_eq!(empty.attrs[1].key, None);
+
+        // the empty slot of an empty tree is the root itself
+        let root_slot = key_path_graph(&KeyProof {
+            key: [0u8; 32],
+            stem: [0u8; 31],
+            depth: 0,
+            extension: ExtensionStatus::None,
+            other_stem: None,
+            value: None,
+            commitments: vec![],
+        });
+        assert_eq!(root_slot.styles, vec![NodeStyle::Empty]);
+        assert!(root_slot.edges.is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    dot::{
+        from_hint::{node_attrs, node_label, node_stem},
+        Graph, Node, NodeStyle,
+    },
+    verkle::{NodeId, NodeKind, WitnessTree},
+};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+/// Witness trees drawn as one graph
+pub(crate) struct Merged {
+    pub graph: Graph,
+    /// Indices of the trees every node is in
+    pub sources: Vec<Vec<usize>>,
+}
+
+// Nodes of two trees are the same if they have the same path, type and stem or key.
+// Leaves and absent leaves count as one type, so that created and deleted values are matched.
+type Identity = (Vec<u8>, NodeStyle, Vec<u8>);
+
+fn identity(tree: &WitnessTree, id: NodeId) -> Identity {
+    let node = tree.node(id);
+    let style = match NodeStyle::of(tree, id) {
+        NodeStyle::ZeroLeaf | NodeStyle::Absent => NodeStyle::Leaf,
+        style => style,
+    };
+    let name = match &node.kind {
+        NodeKind::Extension { stem } | NodeKind::OtherStem { stem } => stem.to_vec(),
+        NodeKind::Leaf { key, .. } | NodeKind::Absent { key } => key.to_vec(),
+        _ => vec![],
+    };
+    (node.path.clone(), style, name)
+}
+
+/// Union of the trees, nodes are labelled as in the last tree they are in
+pub(crate) fn merge_trees(id: &str, trees: &[&WitnessTree]) -> Merged {
+    let mut graph = Graph::new(id);
+    let mut sources: Vec<Vec<usize>> = vec![];
+    let mut by_identity = HashMap::<Identity, Node>::new();
+    let mut has_parent = HashSet::new();
+
+    for (source, tree) in trees.iter().enumerate() {
+        // graph node of every tree node, parents come first
+        let mut drawn = Vec::with_capacity(tree.len());
+        for (id, node) in tree.nodes().iter().enumerate() {
+            let parent = node.parent.map(|parent| drawn[parent]);
+            let depth = parent.map_or(0, |parent: Node| graph.attrs[parent].depth + 1);
+            let attrs = node_attrs(tree, id, depth);
+            let label = (node_label(tree, id), node_stem(node));
+
+            let index = match by_identity.entry(identity(tree, id)) {
+                Entry::Occupied(entry) => {
+                    let index = *entry.get();
+                    graph.nodes[index] = label;
+                    graph.styles[index] = NodeStyle::of(tree, id);
+                    graph.attrs[index] = attrs;
+                    sources[index].push(source);
+                    index
+                }
+                Entry::Vacant(entry) => {
+                    graph.nodes.push(label);
+                    graph.styles.push(NodeStyle::of(tree, id));
+                    graph.attrs.push(attrs);
+                    sources.push(vec![source]);
+                    *entry.insert(graph.nodes.len() - 1)
+                }
+            };
+            drawn.push(index);
+
+            // a node keeps its first parent, so that the union stays a tree
+            if let Some(parent) = parent {
+                if has_parent.insert(index) {
+                    graph.edges.push((parent, index, node.edge_label()));
+                }
+            }
+        }
+    }
+
+    graph.legend = graph.nodes.len();
+    Merged { graph, sources }
+}
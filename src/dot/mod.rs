@@ -1,3 +1,16 @@
+mod aggregate;
+mod diff;
 mod from_hint;
+mod graph;
+mod key_path;
+mod merge;
+mod simplify;
+mod style;
 
-pub(crate) use from_hint::to_dot;
+pub(crate) use aggregate::aggregate_graph;
+pub(crate) use diff::diff_graph;
+pub(crate) use from_hint::{build_graph, heat_color, Highlight, RenderOptions};
+pub(crate) use graph::{Graph, Node, NodeAttrs};
+pub(crate) use key_path::key_path_graph;
+pub(crate) use simplify::{simplify, Simplify};
+pub(crate) use style::NodeStyle;
@@ -0,0 +1,251 @@
+use crate::dot::{Graph, Node, NodeAttrs, NodeStyle};
+use std::collections::HashMap;
+
+/// Options shrinking the graphs of big witnesses
+#[derive(Debug, Clone, Default)]
+pub struct Simplify {
+    /// Children of nodes at this depth are hidden
+    pub max_depth: Option<usize>,
+    /// Stem or key prefix of the only subtree drawn, along with the nodes leading to it
+    pub focus: Option<Vec<u8>>,
+    /// Leave out internal nodes with a single child
+    pub collapse_chains: bool,
+    /// Children past this many are replaced with a "+N more" node
+    pub max_children: Option<usize>,
+}
+
+struct Simplifier<'a> {
+    old: &'a Graph,
+    options: &'a Simplify,
+    /// Indices of the edges leaving every node of `old`
+    children: Vec<Vec<usize>>,
+    /// Whether each node of `old` is in the focused subtrees or leads to them
+    in_focus: Vec<bool>,
+    graph: Graph,
+    /// Node of `graph` of every kept node of `old`
+    kept: HashMap<Node, Node>,
+}
+
+// Whether the stem or key of the node starts with the prefix or is on the way to it
+fn matches_focus(attrs: &NodeAttrs, prefix: &[u8]) -> bool {
+    // tree paths of C1/C2 and leaves put the slot byte after the stem prefix
+    let position = match (&attrs.key, &attrs.stem) {
+        (Some(key), _) => &key[..],
+        (None, Some(stem)) => &stem[..],
+        (None, None) => &attrs.path[..],
+    };
+    position.starts_with(prefix) || prefix.starts_with(position)
+}
+
+impl Simplifier<'_> {
+    fn keep(&mut self, n: Node) -> Node {
+        let index = self.graph.nodes.len();
+        self.graph.nodes.push(self.old.nodes[n].clone());
+        self.graph.styles.push(self.old.styles[n]);
+        self.graph.attrs.push(self.old.attrs[n].clone());
+        self.kept.insert(n, index);
+        index
+    }
+
+    // Draws `n` and what is kept below it, `parent` is the drawn parent and the edge label
+    fn visit(&mut self, n: Node, parent: Option<(Node, String)>) {
+        let old = self.old;
+        let mut children: Vec<usize> = self.children[n]
+            .iter()
+            .copied()
+            .filter(|edge| self.in_focus[old.edges[*edge].1])
+            .collect();
+
+        // a chain of internal nodes is drawn as a single edge labelled with all of its bytes
+        if self.options.collapse_chains
+            && old.styles[n] == NodeStyle::Internal
+            && children.len() == 1
+        {
+            if let Some((parent, label)) = parent {
+                let (_, child, child_label) = &old.edges[children[0]];
+                return self.visit(*child, Some((parent, format!("{label}.{child_label}"))));
+            }
+        }
+
+        let index = self.keep(n);
+        if let Some((parent, label)) = parent {
+            self.graph.edges.push((parent, index, label));
+        }
+
+        let mut hidden = 0;
+        if self
+            .options
+            .max_depth
+            .is_some_and(|max| old.attrs[n].depth >= max)
+        {
+            hidden = children.len();
+            children.clear();
+        }
+        if let Some(max) = self
+            .options
+            .max_children
+            .filter(|max| children.len() > *max)
+        {
+            // highlighted children are kept first, the rest keeps its order
+            if let Some(highlighted) = &old.highlighted {
+                children.sort_by_key(|edge| !highlighted.contains(&old.edges[*edge].1));
+            }
+            hidden = children.len() - max;
+            children.truncate(max);
+            children.sort();
+        }
+
+        for edge in children {
+            let (_, child, label) = &old.edges[edge];
+            self.visit(*child, Some((index, label.clone())));
+        }
+
+        if hidden > 0 {
+            let summary = self.graph.nodes.len();
+            self.graph.nodes.push((format!("+{hidden} more"), None));
+            self.graph.styles.push(NodeStyle::Summary);
+            self.graph.attrs.push(NodeAttrs {
+                depth: old.attrs[n].depth + 1,
+                path: old.attrs[n].path.clone(),
+                ..NodeAttrs::default()
+            });
+            self.graph.edges.push((index, summary, "".to_owned()));
+        }
+    }
+}
+
+/// Copy of the graph with the options applied, legend entries are kept as they are.
+/// Nodes must come after their parents, as `build_graph` adds them.
+pub(crate) fn simplify(old: &Graph, options: &Simplify) -> Graph {
+    let mut children = vec![vec![]; old.nodes.len()];
+    let mut has_parent = vec![false; old.nodes.len()];
+    for (edge, (source, target, _)) in old.edges.iter().enumerate() {
+        children[*source].push(edge);
+        has_parent[*target] = true;
+    }
+
+    // parents precede their children, so going backwards sees the children first
+    let mut in_focus = vec![true; old.nodes.len()];
+    if let Some(prefix) = &options.focus {
+        for n in (0..old.legend).rev() {
+            // other stems lead to the absent keys of the stems in their place
+            let below = children[n].iter().any(|edge| in_focus[old.edges[*edge].1]);
+            in_focus[n] = below || matches_focus(&old.attrs[n], prefix);
+        }
+    }
+
+    let mut simplifier = Simplifier {
+        old,
+        options,
+        children,
+        in_focus,
+        graph: Graph::new(&old.id),
+        kept: HashMap::new(),
+    };
+    for root in (0..old.legend).filter(|n| !has_parent[*n]) {
+        if simplifier.in_focus[root] {
+            simplifier.visit(root, None);
+        }
+    }
+    simplifier.graph.legend = simplifier.graph.nodes.len();
+    for n in old.legend..old.nodes.len() {
+        simplifier.keep(n);
+    }
+
+    let Simplifier {
+        mut graph, kept, ..
+    } = simplifier;
+    graph.node_colors = old
+        .node_colors
+        .iter()
+        .filter_map(|(n, color)| Some((*kept.get(n)?, color.clone())))
+        .collect();
+    graph.highlighted = old.highlighted.as_ref().map(|highlighted| {
+        highlighted
+            .iter()
+            .filter_map(|n| kept.get(n).copied())
+            .collect()
+    });
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dot::{build_graph, RenderOptions},
+        verkle::{testing::witness, WitnessTree},
+    };
+
+    #[test]
+    fn test_simplify() {
+        // ten stems under 0x00 0x01, one more under 0x05
+        let mut keys: Vec<_> = (0..10u8)
+            .map(|i| {
+                let mut key = [0u8; 32];
+                key[1] = 1;
+                key[2] = i;
+                key
+            })
+            .collect();
+        keys.push([5u8; 32]);
+        let (hint, keyvals) = witness(&keys, &[]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let graph = build_graph(&tree, &RenderOptions::default());
+        let summaries = |graph: &Graph| {
+            let mut labels: Vec<_> = (0..graph.nodes.len())
+                .filter(|n| graph.styles[*n] == NodeStyle::Summary)
+                .map(|n| graph.nodes[n].0.clone())
+                .collect();
+            labels.sort();
+            labels
+        };
+
+        let options = Simplify {
+            max_depth: Some(1),
+            ..Simplify::default()
+        };
+        let shallow = simplify(&graph, &options);
+        assert!(shallow.attrs.iter().all(|attrs| attrs.depth <= 2));
+        assert_eq!(summaries(&shallow), vec!["+1 more", "+1 more"]);
+
+        let options = Simplify {
+            max_children: Some(4),
+            ..Simplify::default()
+        };
+        assert_eq!(summaries(&simplify(&graph, &options)), vec!["+6 more"]);
+
+        let options = Simplify {
+            focus: Some(vec![5]),
+            ..Simplify::default()
+        };
+        let focused = simplify(&graph, &options);
+        assert!(focused
+            .attrs
+            .iter()
+            .all(|attrs| attrs.path.first() != Some(&0)));
+        assert_eq!(focused.nodes.len(), 4);
+
+        // a full stem keeps its extension, C1 and leaf below the root and both internal nodes
+        let options = Simplify {
+            focus: Some(keys[3][..31].to_vec()),
+            ..Simplify::default()
+        };
+        let focused = simplify(&graph, &options);
+        assert_eq!(focused.nodes.len(), 6);
+        assert_eq!(
+            focused.nodes.iter().filter(|node| node.1.is_some()).count(),
+            1
+        );
+        assert!(focused.attrs.iter().any(|attrs| attrs.key == Some(keys[3])));
+
+        // the internal node at 0x00 has a single child
+        let options = Simplify {
+            collapse_chains: true,
+            ..Simplify::default()
+        };
+        let collapsed = simplify(&graph, &options);
+        assert_eq!(collapsed.nodes.len(), graph.nodes.len() - 1);
+        assert!(collapsed.edges.iter().any(|edge| edge.2 == "0.1"));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::verkle::{NodeKind, WitnessTree};
+use serde::Serialize;
+
+/// What a drawn node is, each one has its own shape and color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeStyle {
+    Root,
+    Internal,
+    Extension,
+    Suffix,
+    Leaf,
+    ZeroLeaf,
+    Absent,
+    OtherStem,
+    Empty,
+    /// Stands for the hidden nodes of a simplified graph
+    Summary,
+}
+
+impl NodeStyle {
+    pub fn of(tree: &WitnessTree, id: usize) -> Self {
+        let node = tree.node(id);
+        match &node.kind {
+            NodeKind::Internal if node.parent.is_none() => NodeStyle::Root,
+            NodeKind::Internal => NodeStyle::Internal,
+            NodeKind::Extension { .. } => NodeStyle::Extension,
+            NodeKind::OtherStem { .. } => NodeStyle::OtherStem,
+            NodeKind::Empty => NodeStyle::Empty,
+            NodeKind::Suffix { .. } => NodeStyle::Suffix,
+            NodeKind::Leaf { value, .. } if *value == [0u8; 32] => NodeStyle::ZeroLeaf,
+            NodeKind::Leaf { .. } => NodeStyle::Leaf,
+            NodeKind::Absent { .. } => NodeStyle::Absent,
+        }
+    }
+
+    /// Type name, as serialized
+    pub fn name(self) -> &'static str {
+        match self {
+            NodeStyle::Root => "root",
+            NodeStyle::Internal => "internal",
+            NodeStyle::Extension => "extension",
+            NodeStyle::Suffix => "suffix",
+            NodeStyle::Leaf => "leaf",
+            NodeStyle::ZeroLeaf => "zero_leaf",
+            NodeStyle::Absent => "absent",
+            NodeStyle::OtherStem => "other_stem",
+            NodeStyle::Empty => "empty",
+            NodeStyle::Summary => "summary",
+        }
+    }
+
+    /// Graphviz shape
+    pub fn shape(self) -> &'static str {
+        match self {
+            NodeStyle::Root => "doubleoctagon",
+            NodeStyle::Internal => "ellipse",
+            NodeStyle::Extension | NodeStyle::OtherStem => "box",
+            NodeStyle::Suffix => "hexagon",
+            NodeStyle::Leaf | NodeStyle::ZeroLeaf | NodeStyle::Absent => "note",
+            NodeStyle::Empty => "circle",
+            NodeStyle::Summary => "plaintext",
+        }
+    }
+
+    pub fn style(self) -> dot::Style {
+        match self {
+            NodeStyle::Root | NodeStyle::Extension | NodeStyle::Leaf | NodeStyle::ZeroLeaf => {
+                dot::Style::Filled
+            }
+            NodeStyle::Internal | NodeStyle::Suffix | NodeStyle::Summary => dot::Style::Solid,
+            // proofs of absence
+            NodeStyle::Absent | NodeStyle::OtherStem => dot::Style::Dashed,
+            NodeStyle::Empty => dot::Style::Dotted,
+        }
+    }
+
+    pub fn color(self) -> &'static str {
+        match self {
+            NodeStyle::Root => "#9ecae1",
+            NodeStyle::Internal => "#3182bd",
+            NodeStyle::Extension => "#fdae6b",
+            NodeStyle::Suffix => "#e6550d",
+            NodeStyle::Leaf => "#a1d99b",
+            NodeStyle::ZeroLeaf => "#d9d9d9",
+            NodeStyle::Absent => "#de2d26",
+            NodeStyle::OtherStem => "#756bb1",
+            NodeStyle::Empty => "#969696",
+            NodeStyle::Summary => "#636363",
+        }
+    }
+
+    /// Text of the legend entry
+    pub fn description(self) -> &'static str {
+        match self {
+            NodeStyle::Root => "root",
+            NodeStyle::Internal => "internal node",
+            NodeStyle::Extension => "extension of a present stem",
+            NodeStyle::Suffix => "C1/C2 suffix commitment",
+            NodeStyle::Leaf => "leaf",
+            NodeStyle::ZeroLeaf => "zero-valued leaf",
+            NodeStyle::Absent => "absent key",
+            NodeStyle::OtherStem => "other stem found instead",
+            NodeStyle::Empty => "empty slot",
+            NodeStyle::Summary => "hidden nodes",
+        }
+    }
+}
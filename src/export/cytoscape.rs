@@ -0,0 +1,56 @@
+use crate::{
+    dot::Graph,
+    export::{tree_nodes, NodeData},
+};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CyNode {
+    pub data: NodeData,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CyEdgeData {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub label: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CyEdge {
+    pub data: CyEdgeData,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CyElements {
+    pub nodes: Vec<CyNode>,
+    pub edges: Vec<CyEdge>,
+}
+
+/// Cytoscape.js elements JSON, which Cytoscape desktop imports as well
+#[derive(Debug, Serialize)]
+pub struct CyGraph {
+    pub elements: CyElements,
+}
+
+pub fn to_cytoscape(graph: &Graph) -> CyGraph {
+    let nodes = tree_nodes(graph).map(|data| CyNode { data }).collect();
+    let edges = graph
+        .edges
+        .iter()
+        .enumerate()
+        .map(|(id, (source, target, label))| CyEdge {
+            data: CyEdgeData {
+                id: format!("E{id}"),
+                source: graph.node_id(*source),
+                target: graph.node_id(*target),
+                label: label.clone(),
+            },
+        })
+        .collect();
+
+    CyGraph {
+        elements: CyElements { nodes, edges },
+    }
+}
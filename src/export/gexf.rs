@@ -0,0 +1,79 @@
+use crate::{
+    dot::Graph,
+    export::{escape_xml, tree_nodes, ATTRIBUTES},
+};
+use std::fmt::Write;
+
+/// GEXF 1.3 document of the tree for Gephi, node colors are kept as `viz:color`
+pub fn to_gexf(graph: &Graph) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<gexf xmlns="http://gexf.net/1.3" xmlns:viz="http://gexf.net/1.3/viz" version="1.3">"#
+    )?;
+    writeln!(out, r#"  <graph defaultedgetype="directed" mode="static">"#)?;
+    writeln!(out, r#"    <attributes class="node">"#)?;
+    for (id, (name, kind)) in ATTRIBUTES.iter().enumerate() {
+        let kind = match *kind {
+            "int" => "integer",
+            kind => kind,
+        };
+        writeln!(
+            out,
+            r#"      <attribute id="{id}" title="{name}" type="{kind}"/>"#
+        )?;
+    }
+    writeln!(out, "    </attributes>")?;
+
+    writeln!(out, "    <nodes>")?;
+    for node in tree_nodes(graph) {
+        writeln!(
+            out,
+            r#"      <node id="{}" label="{}">"#,
+            node.id,
+            escape_xml(&node.label)
+        )?;
+        writeln!(out, "        <attvalues>")?;
+        for (id, value) in node.attributes().into_iter().enumerate() {
+            if let Some(value) = value {
+                writeln!(
+                    out,
+                    r#"          <attvalue for="{id}" value="{}"/>"#,
+                    escape_xml(&value)
+                )?;
+            }
+        }
+        writeln!(out, "        </attvalues>")?;
+        if let Some((r, g, b)) = rgb(&node.color) {
+            writeln!(out, r#"        <viz:color r="{r}" g="{g}" b="{b}"/>"#)?;
+        }
+        writeln!(out, "      </node>")?;
+    }
+    writeln!(out, "    </nodes>")?;
+
+    writeln!(out, "    <edges>")?;
+    for (id, (source, target, label)) in graph.edges.iter().enumerate() {
+        writeln!(
+            out,
+            r#"      <edge id="{id}" source="{}" target="{}" label="{}"/>"#,
+            graph.node_id(*source),
+            graph.node_id(*target),
+            escape_xml(label)
+        )?;
+    }
+    writeln!(out, "    </edges>")?;
+
+    writeln!(out, "  </graph>\n</gexf>")?;
+    Ok(out)
+}
+
+// Graphviz color names like `gray80` have no RGB form here, such nodes get no color
+fn rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#')?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    match hex.len() {
+        6 => Some((channel(0)?, channel(2)?, channel(4)?)),
+        _ => None,
+    }
+}
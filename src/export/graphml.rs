@@ -0,0 +1,61 @@
+use crate::{
+    dot::Graph,
+    export::{escape_xml, tree_nodes, ATTRIBUTES},
+};
+use std::fmt::Write;
+
+/// GraphML document of the tree, as read by Gephi, Cytoscape, yEd or networkx
+pub fn to_graphml(graph: &Graph) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    for (name, kind) in ATTRIBUTES {
+        writeln!(
+            out,
+            r#"  <key id="{name}" for="node" attr.name="{name}" attr.type="{kind}"/>"#
+        )?;
+    }
+    writeln!(
+        out,
+        r#"  <key id="edge_label" for="edge" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(out, r#"  <graph id="{}" edgedefault="directed">"#, graph.id)?;
+
+    for node in tree_nodes(graph) {
+        writeln!(out, r#"    <node id="{}">"#, node.id)?;
+        writeln!(
+            out,
+            r#"      <data key="label">{}</data>"#,
+            escape_xml(&node.label)
+        )?;
+        for ((name, _), value) in ATTRIBUTES.iter().zip(node.attributes()) {
+            if let Some(value) = value {
+                writeln!(
+                    out,
+                    r#"      <data key="{name}">{}</data>"#,
+                    escape_xml(&value)
+                )?;
+            }
+        }
+        writeln!(out, "    </node>")?;
+    }
+    for (source, target, label) in graph.edges.iter() {
+        writeln!(
+            out,
+            r#"    <edge source="{}" target="{}"><data key="edge_label">{}</data></edge>"#,
+            graph.node_id(*source),
+            graph.node_id(*target),
+            escape_xml(label)
+        )?;
+    }
+
+    writeln!(out, "  </graph>\n</graphml>")?;
+    Ok(out)
+}
@@ -0,0 +1,55 @@
+use crate::{
+    dot::{Graph, NodeStyle},
+    export::tree_nodes,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Mermaid flowchart of the tree for Markdown docs. Mermaid has no node attributes,
+/// so only the labels and a class per node type are kept
+pub fn to_mermaid(graph: &Graph) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, "flowchart TD")?;
+
+    let mut classes = BTreeMap::<NodeStyle, Vec<String>>::new();
+    for node in tree_nodes(graph) {
+        writeln!(out, r#"    {}["{}"]"#, node.id, escape(&node.label))?;
+        classes.entry(node.kind).or_default().push(node.id);
+    }
+    for (source, target, label) in graph.edges.iter() {
+        match label.is_empty() {
+            true => writeln!(
+                out,
+                "    {} --> {}",
+                graph.node_id(*source),
+                graph.node_id(*target)
+            )?,
+            false => writeln!(
+                out,
+                "    {} -->|{}| {}",
+                graph.node_id(*source),
+                escape(label),
+                graph.node_id(*target)
+            )?,
+        }
+    }
+
+    for (style, nodes) in classes {
+        let color = style.color();
+        let css = match style.style() {
+            dot::Style::Filled => format!("fill:{color},stroke:#333"),
+            dot::Style::Dashed | dot::Style::Dotted => {
+                format!("fill:#fff,stroke:{color},stroke-dasharray:5 3")
+            }
+            _ => format!("fill:#fff,stroke:{color}"),
+        };
+        writeln!(out, "    classDef {} {css}", style.name())?;
+        writeln!(out, "    class {} {}", nodes.join(","), style.name())?;
+    }
+
+    Ok(out)
+}
+
+// Quotes end a label and raw newlines end a statement
+fn escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', "<br>")
+}
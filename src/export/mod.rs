@@ -0,0 +1,194 @@
+mod cytoscape;
+mod gexf;
+mod graphml;
+mod mermaid;
+
+pub(crate) use cytoscape::to_cytoscape;
+pub(crate) use gexf::to_gexf;
+pub(crate) use graphml::to_graphml;
+pub(crate) use mermaid::to_mermaid;
+
+use crate::dot::{Graph, Node, NodeStyle};
+use serde::Serialize;
+
+/// Names and GraphML/GEXF types of the node attributes, in the order of `NodeData::attributes`
+pub(crate) const ATTRIBUTES: [(&str, &str); 9] = [
+    ("type", "string"),
+    ("depth", "int"),
+    ("path", "string"),
+    ("commitment", "string"),
+    ("stem", "string"),
+    ("key", "string"),
+    ("value", "string"),
+    ("decoded", "string"),
+    ("color", "string"),
+];
+
+/// Attributes of a tree node shared by the exporters
+#[derive(Debug, Serialize)]
+pub struct NodeData {
+    pub id: String,
+    pub label: String,
+    #[serde(rename = "type")]
+    pub kind: NodeStyle,
+    pub depth: usize,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stem: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// The value read as a little-endian integer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<String>,
+    pub color: String,
+}
+
+impl NodeData {
+    pub fn new(graph: &Graph, n: Node) -> Self {
+        let attrs = &graph.attrs[n];
+        NodeData {
+            id: graph.node_id(n),
+            label: graph.nodes[n].0.clone(),
+            kind: graph.styles[n],
+            depth: attrs.depth,
+            path: format!("0x{}", hex::encode(&attrs.path)),
+            commitment: attrs.commitment.clone(),
+            stem: graph.nodes[n].1.as_ref().map(|stem| format!("0x{stem}")),
+            key: attrs.key.map(|key| format!("0x{}", hex::encode(key))),
+            value: attrs.value.map(|value| format!("0x{}", hex::encode(value))),
+            decoded: attrs.value.as_ref().map(decode_value),
+            color: graph.color(n).to_owned(),
+        }
+    }
+
+    /// Values in the order of `ATTRIBUTES`, None if the node doesn't have it
+    pub fn attributes(&self) -> [Option<String>; ATTRIBUTES.len()] {
+        [
+            Some(self.kind.name().to_owned()),
+            Some(self.depth.to_string()),
+            Some(self.path.clone()),
+            self.commitment.clone(),
+            self.stem.clone(),
+            self.key.clone(),
+            self.value.clone(),
+            self.decoded.clone(),
+            Some(self.color.clone()),
+        ]
+    }
+}
+
+// Legend entries are not part of the tree, so they are not exported
+fn tree_nodes(graph: &Graph) -> impl Iterator<Item = NodeData> + '_ {
+    (0..graph.legend).map(|n| NodeData::new(graph, n))
+}
+
+/// Leaf values (balances, nonces, code sizes) are stored as little-endian integers
+pub fn decode_value(value: &[u8; 32]) -> String {
+    // most significant byte first, divided by 10 until nothing is left
+    let mut number: Vec<u16> = value.iter().rev().map(|byte| *byte as u16).collect();
+    let mut digits = vec![];
+    while number.iter().any(|byte| *byte != 0) {
+        let mut rem = 0;
+        for byte in number.iter_mut() {
+            let current = rem * 256 + *byte;
+            *byte = current / 10;
+            rem = current % 10;
+        }
+        digits.push(char::from(b'0' + rem as u8));
+    }
+    match digits.is_empty() {
+        true => "0".to_owned(),
+        false => digits.into_iter().rev().collect(),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dot::{build_graph, RenderOptions},
+        verkle::{testing::witness, WitnessTree},
+    };
+
+    #[test]
+    fn test_decode_value() {
+        let mut value = [0u8; 32];
+        assert_eq!(decode_value(&value), "0");
+        value[0] = 0x39;
+        value[1] = 0x30;
+        assert_eq!(decode_value(&value), "12345");
+        assert_eq!(
+            decode_value(&[0xff; 32]),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    #[test]
+    fn test_exports_have_every_tree_node() {
+        let mut other = [0u8; 32];
+        other[1] = 1;
+        let (hint, keyvals) = witness(&[[0u8; 32], [1u8; 32]], &[other, [5u8; 32]]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let options = RenderOptions {
+            legend: true,
+            ..RenderOptions::default()
+        };
+        let graph = build_graph(&tree, &options);
+
+        let graphml = to_graphml(&graph).unwrap();
+        let gexf = to_gexf(&graph).unwrap();
+        let mermaid = to_mermaid(&graph).unwrap();
+        let cytoscape = to_cytoscape(&graph);
+        assert_eq!(graphml.matches("<node ").count(), tree.len());
+        assert_eq!(gexf.matches("<node ").count(), tree.len());
+        assert_eq!(mermaid.matches("[\"").count(), tree.len());
+        assert_eq!(cytoscape.elements.nodes.len(), tree.len());
+        assert_eq!(graphml.matches("<edge ").count(), tree.len() - 1);
+        assert_eq!(cytoscape.elements.edges.len(), tree.len() - 1);
+
+        // leaves carry their key, value and depth
+        let leaf = cytoscape
+            .elements
+            .nodes
+            .iter()
+            .find(|node| node.data.key == Some(format!("0x{}", hex::encode([1u8; 32]))))
+            .unwrap();
+        assert_eq!(
+            leaf.data.decoded.as_deref(),
+            Some(decode_value(&[1u8; 32]).as_str())
+        );
+        assert_eq!(leaf.data.depth, 3);
+        assert!(graphml.contains(&format!(
+            "<data key=\"decoded\">{}</data>",
+            decode_value(&[1u8; 32])
+        )));
+        let json = graph.to_json();
+        let leaf = json
+            .nodes
+            .iter()
+            .find(|node| node.key == Some(format!("0x{}", hex::encode([1u8; 32]))))
+            .unwrap();
+        assert_eq!(leaf.value, Some(format!("0x{}", hex::encode([1u8; 32]))));
+        assert_eq!(leaf.depth, 3);
+        assert_eq!(leaf.highlighted, None);
+    }
+}
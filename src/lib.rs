@@ -2,11 +2,15 @@ mod cli;
 mod config;
 mod dot;
 mod error;
+mod export;
+mod render;
 mod server;
+mod tui;
 mod types;
 pub mod verkle;
+mod witness;
 
-pub use self::cli::Args;
+pub use self::cli::{Args, Command};
 pub use crate::config::Config;
 
 pub use server::run_http as run;
@@ -4,6 +4,13 @@ use verkle_tree_img::{run, Args, Config};
 async fn main() -> std::io::Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
     let args = Args::default();
+    if let Some(command) = args.command {
+        if let Err(err) = command.run().await {
+            eprintln!("Error: {err}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     let config = Config::from_file(args.config_path).expect("Failed to parse config");
     run(config).await
 }
@@ -0,0 +1,92 @@
+use crate::{
+    dot::Graph,
+    render::{Engine, ImageFormat, LayoutOptions, Renderer},
+};
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+/// Runs the `dot` executable of Graphviz
+pub struct Graphviz;
+
+impl Renderer for Graphviz {
+    fn supports(&self, _format: ImageFormat) -> bool {
+        true
+    }
+
+    fn supports_engine(&self, _engine: Engine) -> bool {
+        true
+    }
+
+    // The DOT source is piped to stdin and the image is read from stdout.
+    // Blocks until graphviz exits, so it must not run on the async executor.
+    fn render(
+        &self,
+        graph: &Graph,
+        format: ImageFormat,
+        layout: &LayoutOptions,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let source = dot_source(graph, layout)?;
+
+        let mut child = Command::new("dot")
+            .arg(format!("-K{}", layout.engine.name()))
+            .arg(format!("-T{}", format.name()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| anyhow::anyhow!("Failed to run graphviz: {err}"))?;
+
+        // writing from another thread, graphviz may fill stdout before it reads all the input
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Graphviz stdin is not piped"))?;
+        let writer = std::thread::spawn(move || stdin.write_all(&source));
+
+        let output = child.wait_with_output()?;
+        let written = writer
+            .join()
+            .map_err(|_| anyhow::anyhow!("Graphviz writer panicked"))?;
+
+        // a failed graphviz closes stdin early, its exit status tells more than the broken pipe
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Graphviz exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        written?;
+
+        Ok(output.stdout)
+    }
+}
+
+/// DOT source with the layout options as graph attributes, so that it draws the same with
+/// any graphviz program
+pub(crate) fn dot_source(graph: &Graph, layout: &LayoutOptions) -> Result<Vec<u8>, anyhow::Error> {
+    let mut source = vec![];
+    graph.write_dot(&mut source)?;
+
+    let foreground = layout.theme.foreground();
+    let mut attrs = format!(
+        "    layout={}; rankdir={}; nodesep={};\n",
+        layout.engine.name(),
+        layout.rankdir.name(),
+        layout.nodesep
+    );
+    if let Some(background) = layout.theme.background() {
+        attrs.push_str(&format!(
+            "    bgcolor=\"{background}\";\n    node [fontcolor=\"{foreground}\"];\n    edge [color=\"{foreground}\", fontcolor=\"{foreground}\"];\n"
+        ));
+    }
+    // right after the `digraph id {` line
+    let start = source
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map_or(source.len(), |n| n + 1);
+    source.splice(start..start, attrs.into_bytes());
+    Ok(source)
+}
@@ -0,0 +1,221 @@
+mod graphviz;
+mod raster;
+mod svg;
+mod text;
+
+pub(crate) use graphviz::{dot_source, Graphviz};
+pub(crate) use raster::{preview, rasterize, thumbnail, RasterFormat};
+pub(crate) use svg::NativeSvg;
+pub(crate) use text::{node_line, text_tree, TextOptions};
+
+use crate::dot::Graph;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl ImageFormat {
+    /// Name of the format for `dot -T`
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageFormat::Svg => "svg",
+            ImageFormat::Png => "png",
+            ImageFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Turns a graph into an image
+pub(crate) trait Renderer: Send + Sync {
+    fn supports(&self, format: ImageFormat) -> bool {
+        format == ImageFormat::Svg
+    }
+
+    fn supports_engine(&self, engine: Engine) -> bool {
+        engine == Engine::Dot
+    }
+
+    fn render(
+        &self,
+        graph: &Graph,
+        format: ImageFormat,
+        layout: &LayoutOptions,
+    ) -> Result<Vec<u8>, anyhow::Error>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Built-in layout, needs nothing installed
+    #[default]
+    Native,
+    /// Needs the `dot` executable in `PATH`
+    Graphviz,
+}
+
+/// How the nodes are placed, named after the graphviz programs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    /// Ranks of the tree one under the other
+    #[default]
+    Dot,
+    /// Ranks on circles around the root
+    Twopi,
+    /// Force-directed, for the biggest witnesses. Graphviz only
+    Sfdp,
+}
+
+impl Engine {
+    pub fn name(self) -> &'static str {
+        match self {
+            Engine::Dot => "dot",
+            Engine::Twopi => "twopi",
+            Engine::Sfdp => "sfdp",
+        }
+    }
+}
+
+/// Direction the edges point to, as graphviz `rankdir`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RankDir {
+    #[default]
+    #[serde(alias = "tb")]
+    Tb,
+    #[serde(alias = "lr")]
+    Lr,
+    #[serde(alias = "bt")]
+    Bt,
+    #[serde(alias = "rl")]
+    Rl,
+}
+
+impl RankDir {
+    pub fn name(self) -> &'static str {
+        match self {
+            RankDir::Tb => "TB",
+            RankDir::Lr => "LR",
+            RankDir::Bt => "BT",
+            RankDir::Rl => "RL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Light,
+    /// Light text and outlined nodes on a dark background
+    Dark,
+}
+
+impl Theme {
+    /// None keeps the image transparent
+    pub fn background(self) -> Option<&'static str> {
+        match self {
+            Theme::Light => None,
+            Theme::Dark => Some("#1e1e1e"),
+        }
+    }
+
+    /// Color of the text and of the edges without a color of their own
+    pub fn foreground(self) -> &'static str {
+        match self {
+            Theme::Light => "black",
+            Theme::Dark => "#e0e0e0",
+        }
+    }
+
+    /// Fill of the nodes that aren't filled with their color
+    pub fn node_fill(self) -> &'static str {
+        match self {
+            Theme::Light => "white",
+            Theme::Dark => "#2b2b2b",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct LayoutOptions {
+    pub engine: Engine,
+    pub rankdir: RankDir,
+    /// Space between the nodes of a rank, in inches
+    pub nodesep: f64,
+    /// Label lines longer than this many characters are cut
+    pub label_width: Option<usize>,
+    pub theme: Theme,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            engine: Engine::Dot,
+            rankdir: RankDir::Tb,
+            // the graphviz default
+            nodesep: 0.25,
+            label_width: None,
+            theme: Theme::Light,
+        }
+    }
+}
+
+/// Size of the thumbnails when the request doesn't ask for one
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct ThumbnailConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Largest width or height a request may ask for
+    pub max_size: u32,
+}
+
+impl Default for ThumbnailConfig {
+    fn default() -> Self {
+        Self {
+            width: 320,
+            height: 180,
+            max_size: 1200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RenderConfig {
+    pub backend: Backend,
+    pub layout: LayoutOptions,
+    pub thumbnail: ThumbnailConfig,
+    /// Largest number of pixels of a full-size PNG drawn by the native backend,
+    /// bigger trees are scaled down to it
+    pub max_pixels: u64,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            backend: Backend::default(),
+            layout: LayoutOptions::default(),
+            thumbnail: ThumbnailConfig::default(),
+            // 64 MB of RGBA
+            max_pixels: 4096 * 4096,
+        }
+    }
+}
+
+impl RenderConfig {
+    pub(crate) fn renderer(&self) -> Box<dyn Renderer> {
+        match self.backend {
+            Backend::Native => Box::new(NativeSvg {
+                max_pixels: self.max_pixels,
+            }),
+            Backend::Graphviz => Box::new(Graphviz),
+        }
+    }
+}
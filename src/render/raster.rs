@@ -0,0 +1,202 @@
+// SVG to PNG/WebP in process with resvg, so neither Graphviz nor a browser is needed for the
+// raster images. The fonts are looked up once, the images ask for `monospace` which is mapped
+// to the first monospaced system font.
+
+use resvg::{
+    tiny_skia::{Color, Pixmap, Transform},
+    usvg::{self, fontdb},
+};
+use serde::Deserialize;
+use std::sync::{Arc, OnceLock};
+
+/// Open Graph image size
+const PREVIEW_SIZE: (u32, u32) = (1200, 630);
+/// Longest side of full-size raster images, bigger trees are scaled down to it
+const MAX_SIDE: f32 = 8192.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RasterFormat {
+    #[default]
+    Png,
+    /// Lossless
+    Webp,
+}
+
+impl RasterFormat {
+    pub fn mime(self) -> &'static str {
+        match self {
+            RasterFormat::Png => "image/png",
+            RasterFormat::Webp => "image/webp",
+        }
+    }
+}
+
+fn fonts() -> Arc<fontdb::Database> {
+    static FONTS: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = fontdb::Database::new();
+            fonts.load_system_fonts();
+            let monospace = fonts
+                .faces()
+                .find(|face| face.monospaced)
+                .and_then(|face| face.families.first())
+                .map(|(family, _)| family.clone());
+            if let Some(family) = monospace {
+                fonts.set_monospace_family(family);
+            }
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+fn parse(svg: &[u8]) -> Result<usvg::Tree, anyhow::Error> {
+    let options = usvg::Options {
+        fontdb: fonts(),
+        ..usvg::Options::default()
+    };
+    usvg::Tree::from_data(svg, &options).map_err(|err| anyhow::anyhow!("Incorrect SVG: {err}"))
+}
+
+fn new_pixmap(width: u32, height: u32) -> Result<Pixmap, anyhow::Error> {
+    let mut pixmap = Pixmap::new(width.max(1), height.max(1))
+        .ok_or_else(|| anyhow::anyhow!("Can't allocate a {width}x{height} image"))?;
+    // transparent images look broken on dark pages, the dark theme paints its own background
+    pixmap.fill(Color::WHITE);
+    Ok(pixmap)
+}
+
+// Draws the tree scaled to fit into the box and centered in it
+fn draw_fit(pixmap: &mut Pixmap, tree: &usvg::Tree, (x, y, width, height): (f32, f32, f32, f32)) {
+    let size = tree.size();
+    let scale = (width / size.width()).min(height / size.height());
+    let (dx, dy) = (
+        x + (width - size.width() * scale) / 2.0,
+        y + (height - size.height() * scale) / 2.0,
+    );
+    resvg::render(
+        tree,
+        Transform::from_scale(scale, scale).post_translate(dx, dy),
+        &mut pixmap.as_mut(),
+    );
+}
+
+fn encode(pixmap: &Pixmap, format: RasterFormat) -> Result<Vec<u8>, anyhow::Error> {
+    match format {
+        RasterFormat::Png => pixmap
+            .encode_png()
+            .map_err(|err| anyhow::anyhow!("Failed to encode PNG: {err}")),
+        RasterFormat::Webp => {
+            // tiny-skia keeps premultiplied alpha
+            let data: Vec<u8> = pixmap
+                .pixels()
+                .iter()
+                .flat_map(|pixel| {
+                    let color = pixel.demultiply();
+                    [color.red(), color.green(), color.blue(), color.alpha()]
+                })
+                .collect();
+            let mut image = vec![];
+            image_webp::WebPEncoder::new(&mut image)
+                .encode(
+                    &data,
+                    pixmap.width(),
+                    pixmap.height(),
+                    image_webp::ColorType::Rgba8,
+                )
+                .map_err(|err| anyhow::anyhow!("Failed to encode WebP: {err}"))?;
+            Ok(image)
+        }
+    }
+}
+
+/// The image at its own size, or scaled down if its longest side is over `MAX_SIDE`
+/// or it has more than `max_pixels` pixels
+pub(crate) fn rasterize(
+    svg: &[u8],
+    format: RasterFormat,
+    max_pixels: u64,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let tree = parse(svg)?;
+    let size = tree.size();
+    let area = size.width() as f64 * size.height() as f64;
+    let scale = (MAX_SIDE / size.width().max(size.height()))
+        .min((max_pixels as f64 / area).sqrt() as f32)
+        .min(1.0);
+    // rounded down, so that the cap holds
+    let (width, height) = (
+        (size.width() * scale).floor(),
+        (size.height() * scale).floor(),
+    );
+    let mut pixmap = new_pixmap(width as u32, height as u32)?;
+    draw_fit(&mut pixmap, &tree, (0.0, 0.0, width, height));
+    encode(&pixmap, format)
+}
+
+/// The image scaled to fit into `width`x`height`, keeping its aspect ratio
+pub(crate) fn thumbnail(
+    svg: &[u8],
+    (width, height): (u32, u32),
+    format: RasterFormat,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let tree = parse(svg)?;
+    let size = tree.size();
+    let scale = (width as f32 / size.width()).min(height as f32 / size.height());
+    let (width, height) = (size.width() * scale, size.height() * scale);
+    let mut pixmap = new_pixmap(width.round() as u32, height.round() as u32)?;
+    draw_fit(&mut pixmap, &tree, (0.0, 0.0, width, height));
+    encode(&pixmap, format)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// `PREVIEW_SIZE` PNG for link unfurls: a title and a subtitle over the image
+pub(crate) fn preview(svg: &[u8], title: &str, subtitle: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let (width, height) = PREVIEW_SIZE;
+    let header = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace"><rect width="100%" height="100%" fill="#f7f7f7"/><text x="40" y="70" font-size="44" font-weight="bold" fill="#222">{}</text><text x="40" y="112" font-size="26" fill="#555">{}</text><line x1="40" y1="136" x2="{}" y2="136" stroke="#ccc" stroke-width="2"/></svg>"##,
+        escape(title),
+        escape(subtitle),
+        width - 40
+    );
+
+    let mut pixmap = new_pixmap(width, height)?;
+    resvg::render(
+        &parse(header.as_bytes())?,
+        Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+    let image = (40.0, 156.0, width as f32 - 80.0, height as f32 - 186.0);
+    draw_fit(&mut pixmap, &parse(svg)?, image);
+    encode(&pixmap, RasterFormat::Png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raster_formats() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="400" height="100"><rect width="400" height="100" fill="red"/></svg>"#;
+
+        let png = thumbnail(svg, (200, 200), RasterFormat::Png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        // width and height of the IHDR chunk
+        assert_eq!(png[16..24], [0, 0, 0, 200, 0, 0, 0, 50]);
+
+        let webp = rasterize(svg, RasterFormat::Webp, u64::MAX).unwrap();
+        assert!(webp.starts_with(b"RIFF") && &webp[8..12] == b"WEBP");
+
+        // a quarter of the pixels is half of the width and the height
+        let png = rasterize(svg, RasterFormat::Png, 10_000).unwrap();
+        assert_eq!(png[16..24], [0, 0, 0, 200, 0, 0, 0, 50]);
+
+        let preview = preview(svg, "Block 1", "1 keys").unwrap();
+        assert_eq!(preview[16..24], [0, 0, 4, 176, 0, 0, 2, 118]);
+    }
+}
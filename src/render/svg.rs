@@ -0,0 +1,662 @@
+// Pure Rust tree layout and SVG writer. Every node is centered over its children and the
+// subtrees of siblings are put side by side, so the picture is as wide as the widest rank
+// of leaves. Unconnected nodes (legend entries) are laid out as separate trees. Other rank
+// directions turn that layout, the radial one wraps it around the root.
+
+use crate::{
+    dot::{Graph, Node},
+    export::{NodeData, ATTRIBUTES},
+    render::{
+        rasterize, Engine, ImageFormat, LayoutOptions, RankDir, RasterFormat, Renderer, Theme,
+    },
+};
+use std::{f64::consts::TAU, fmt::Write};
+
+const FONT_SIZE: f64 = 12.0;
+/// Width of a character of the monospace font at `FONT_SIZE`
+const CHAR_WIDTH: f64 = 7.2;
+const LINE_HEIGHT: f64 = 15.0;
+const PADDING: f64 = 10.0;
+const POINTS_PER_INCH: f64 = 72.0;
+const V_GAP: f64 = 60.0;
+const MARGIN: f64 = 20.0;
+
+pub struct NativeSvg {
+    /// Largest PNG image in pixels, bigger ones are scaled down
+    pub max_pixels: u64,
+}
+
+impl Renderer for NativeSvg {
+    fn supports(&self, format: ImageFormat) -> bool {
+        format != ImageFormat::Pdf
+    }
+
+    fn supports_engine(&self, engine: Engine) -> bool {
+        engine != Engine::Sfdp
+    }
+
+    fn render(
+        &self,
+        graph: &Graph,
+        format: ImageFormat,
+        layout: &LayoutOptions,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let svg = write_svg(graph, &Layout::new(graph, layout), layout.theme)?.into_bytes();
+        match format {
+            ImageFormat::Svg => Ok(svg),
+            ImageFormat::Png => rasterize(&svg, RasterFormat::Png, self.max_pixels),
+            ImageFormat::Pdf => Err(anyhow::anyhow!(
+                "The native renderer can't draw {}",
+                format.name()
+            )),
+        }
+    }
+}
+
+struct Layout {
+    /// Center of every node
+    x: Vec<f64>,
+    y: Vec<f64>,
+    sizes: Vec<(f64, f64)>,
+    width: f64,
+    height: f64,
+    /// Direction of the edges, None if they go out of the root in every direction
+    rankdir: Option<RankDir>,
+}
+
+fn node_size(graph: &Graph, n: Node) -> (f64, f64) {
+    let label = graph.label(n);
+    let lines = label.lines().count().max(1) as f64;
+    let chars = label.lines().map(|l| l.chars().count()).max().unwrap_or(0) as f64;
+    let (width, height) = (
+        chars * CHAR_WIDTH + 2.0 * PADDING,
+        lines * LINE_HEIGHT + PADDING,
+    );
+    match graph.styles[n].shape() {
+        // the label has to fit into the ellipse
+        "ellipse" => (width * 1.3, height * 1.4),
+        "circle" => {
+            let side = width.max(height) * 1.2;
+            (side, side)
+        }
+        "doubleoctagon" | "hexagon" => (width + 2.0 * PADDING, height + PADDING),
+        _ => (width, height),
+    }
+}
+
+/// Children and depths of the nodes, with the parents before their children
+struct Tree {
+    children: Vec<Vec<Node>>,
+    roots: Vec<Node>,
+    order: Vec<Node>,
+    depth: Vec<usize>,
+}
+
+impl Tree {
+    fn new(graph: &Graph) -> Self {
+        let count = graph.nodes.len();
+        let mut children = vec![vec![]; count];
+        let mut has_parent = vec![false; count];
+        for (source, target, _) in graph.edges.iter() {
+            children[*source].push(*target);
+            has_parent[*target] = true;
+        }
+        let roots: Vec<_> = (0..count).filter(|n| !has_parent[*n]).collect();
+
+        // preorder walk
+        let mut order = Vec::with_capacity(count);
+        let mut depth = vec![0; count];
+        let mut stack: Vec<_> = roots.iter().rev().copied().collect();
+        while let Some(n) = stack.pop() {
+            order.push(n);
+            for child in children[n].iter().rev() {
+                depth[*child] = depth[n] + 1;
+                stack.push(*child);
+            }
+        }
+
+        Tree {
+            children,
+            roots,
+            order,
+            depth,
+        }
+    }
+
+    /// Width of the subtree of every node, siblings `gap` apart
+    fn subtree_widths(&self, sizes: &[(f64, f64)], gap: f64) -> Vec<f64> {
+        let mut subtree = vec![0.0f64; sizes.len()];
+        for n in self.order.iter().rev() {
+            let children = &self.children[*n];
+            let below: f64 = children.iter().map(|c| subtree[*c]).sum::<f64>()
+                + gap * children.len().saturating_sub(1) as f64;
+            subtree[*n] = sizes[*n].0.max(below);
+        }
+        subtree
+    }
+}
+
+impl Layout {
+    fn new(graph: &Graph, options: &LayoutOptions) -> Self {
+        let sizes: Vec<_> = (0..graph.nodes.len())
+            .map(|n| node_size(graph, n))
+            .collect();
+        let tree = Tree::new(graph);
+        let gap = options.nodesep * POINTS_PER_INCH;
+
+        if options.engine == Engine::Twopi {
+            return Layout::radial(&tree, sizes, gap);
+        }
+        match options.rankdir {
+            RankDir::Tb => Layout::ranked(&tree, sizes, gap),
+            RankDir::Bt => Layout::ranked(&tree, sizes, gap).flip(),
+            // laid out top down with the sizes swapped, then turned
+            RankDir::Lr | RankDir::Rl => {
+                let turned = sizes.iter().map(|(w, h)| (*h, *w)).collect();
+                let layout = Layout::ranked(&tree, turned, gap).transpose();
+                match options.rankdir {
+                    RankDir::Rl => layout.flip(),
+                    _ => layout,
+                }
+            }
+        }
+    }
+
+    // Every node is centered over its children and the subtrees of siblings are side by side
+    fn ranked(tree: &Tree, sizes: Vec<(f64, f64)>, gap: f64) -> Self {
+        let count = sizes.len();
+
+        // each rank is as high as its highest node
+        let ranks = tree
+            .order
+            .iter()
+            .map(|n| tree.depth[*n])
+            .max()
+            .map_or(0, |d| d + 1);
+        let mut rank_height = vec![0.0f64; ranks];
+        for n in tree.order.iter() {
+            rank_height[tree.depth[*n]] = rank_height[tree.depth[*n]].max(sizes[*n].1);
+        }
+        let mut rank_top = vec![MARGIN; ranks];
+        for rank in 1..ranks {
+            rank_top[rank] = rank_top[rank - 1] + rank_height[rank - 1] + V_GAP;
+        }
+
+        let subtree = tree.subtree_widths(&sizes, gap);
+        let mut x = vec![0.0; count];
+        let mut y = vec![0.0; count];
+        let mut left = vec![0.0; count];
+        let mut next = MARGIN;
+        for root in tree.roots.iter() {
+            left[*root] = next;
+            next += subtree[*root] + 2.0 * gap;
+        }
+        for n in tree.order.iter() {
+            let depth = tree.depth[*n];
+            x[*n] = left[*n] + subtree[*n] / 2.0;
+            y[*n] = rank_top[depth] + rank_height[depth] / 2.0;
+
+            let children = &tree.children[*n];
+            let below: f64 = children.iter().map(|c| subtree[*c]).sum::<f64>()
+                + gap * children.len().saturating_sub(1) as f64;
+            let mut child_left = left[*n] + (subtree[*n] - below) / 2.0;
+            for child in children.iter() {
+                left[*child] = child_left;
+                child_left += subtree[*child] + gap;
+            }
+        }
+
+        let width = next - 2.0 * gap + MARGIN;
+        let height = match ranks {
+            0 => 2.0 * MARGIN,
+            _ => rank_top[ranks - 1] + rank_height[ranks - 1] + MARGIN,
+        };
+
+        Layout {
+            x,
+            y,
+            sizes,
+            width: width.max(2.0 * MARGIN),
+            height,
+            rankdir: Some(RankDir::Tb),
+        }
+    }
+
+    // Ranks on circles around the first root, every subtree gets a sector as wide as its
+    // subtree in the ranked layout. Other roots (the legend) are put in a row below.
+    fn radial(tree: &Tree, sizes: Vec<(f64, f64)>, gap: f64) -> Self {
+        let count = sizes.len();
+        let subtree = tree.subtree_widths(&sizes, gap);
+        let Some(&root) = tree.roots.first() else {
+            return Layout::ranked(tree, sizes, gap);
+        };
+
+        let extent = |n: Node| sizes[n].0.max(sizes[n].1);
+        let ranks = tree.depth.iter().max().map_or(0, |d| d + 1);
+        let mut rank_extent = vec![0.0f64; ranks];
+        let mut start = vec![0.0f64; count];
+        let mut drawn = vec![false; count];
+        let mut stack = vec![root];
+        while let Some(n) = stack.pop() {
+            drawn[n] = true;
+            rank_extent[tree.depth[n]] = rank_extent[tree.depth[n]].max(extent(n));
+            let children = &tree.children[n];
+            let below: f64 = children.iter().map(|c| subtree[*c]).sum::<f64>()
+                + gap * children.len().saturating_sub(1) as f64;
+            let mut child_start = start[n] + (subtree[n] - below) / 2.0;
+            for child in children.iter() {
+                start[*child] = child_start;
+                child_start += subtree[*child] + gap;
+                stack.push(*child);
+            }
+        }
+
+        // the first ring is big enough for the whole tree width to fit around it
+        let circumference = subtree[root] + gap;
+        let mut radius = vec![0.0f64; ranks];
+        for rank in 1..ranks {
+            let step = (rank_extent[rank - 1] + rank_extent[rank]) / 2.0 + V_GAP;
+            radius[rank] = (radius[rank - 1] + step).max(circumference / TAU);
+        }
+        let outer = radius.last().copied().unwrap_or_default()
+            + rank_extent.last().copied().unwrap_or_default() / 2.0;
+        let center = MARGIN + outer;
+
+        let mut x = vec![0.0; count];
+        let mut y = vec![0.0; count];
+        for n in tree.order.iter().filter(|n| drawn[**n]) {
+            let angle = TAU * (start[*n] + subtree[*n] / 2.0) / circumference;
+            let r = radius[tree.depth[*n]];
+            x[*n] = center + r * angle.cos();
+            y[*n] = center + r * angle.sin();
+        }
+
+        // the rest is laid out as ranks under the circle
+        let others = Tree {
+            children: tree.children.clone(),
+            roots: tree.roots[1..].to_vec(),
+            order: tree.order.iter().copied().filter(|n| !drawn[*n]).collect(),
+            depth: tree.depth.clone(),
+        };
+        let mut width = 2.0 * center;
+        let mut height = 2.0 * center;
+        if !others.roots.is_empty() {
+            let below = Layout::ranked(&others, sizes.clone(), gap);
+            for n in others.order.iter() {
+                x[*n] = below.x[*n];
+                y[*n] = below.y[*n] + height - MARGIN;
+            }
+            width = width.max(below.width);
+            height += below.height - MARGIN;
+        }
+
+        Layout {
+            x,
+            y,
+            sizes,
+            width,
+            height,
+            rankdir: None,
+        }
+    }
+
+    // Swaps the axes of a layout made with swapped sizes, top down becomes left to right
+    fn transpose(self) -> Self {
+        Layout {
+            x: self.y,
+            y: self.x,
+            sizes: self.sizes.iter().map(|(w, h)| (*h, *w)).collect(),
+            width: self.height,
+            height: self.width,
+            rankdir: self.rankdir.map(|rankdir| match rankdir {
+                RankDir::Tb => RankDir::Lr,
+                RankDir::Lr => RankDir::Tb,
+                RankDir::Bt => RankDir::Rl,
+                RankDir::Rl => RankDir::Bt,
+            }),
+        }
+    }
+
+    // Mirrors the ranks, top down becomes bottom up and left to right becomes right to left
+    fn flip(mut self) -> Self {
+        match self.rankdir {
+            Some(RankDir::Tb) => {
+                self.y.iter_mut().for_each(|y| *y = self.height - *y);
+                self.rankdir = Some(RankDir::Bt);
+            }
+            Some(RankDir::Lr) => {
+                self.x.iter_mut().for_each(|x| *x = self.width - *x);
+                self.rankdir = Some(RankDir::Rl);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    // Where an edge leaves the source and enters the target
+    fn edge_ends(&self, source: Node, target: Node) -> ((f64, f64), (f64, f64)) {
+        let side = |n: Node, (dx, dy): (f64, f64)| {
+            (
+                self.x[n] + dx * self.sizes[n].0 / 2.0,
+                self.y[n] + dy * self.sizes[n].1 / 2.0,
+            )
+        };
+        let direction = match self.rankdir {
+            Some(RankDir::Tb) => (0.0, 1.0),
+            Some(RankDir::Bt) => (0.0, -1.0),
+            Some(RankDir::Lr) => (1.0, 0.0),
+            Some(RankDir::Rl) => (-1.0, 0.0),
+            None => {
+                return (
+                    self.border(source, (self.x[target], self.y[target])),
+                    self.border(target, (self.x[source], self.y[source])),
+                )
+            }
+        };
+        (
+            side(source, direction),
+            side(target, (-direction.0, -direction.1)),
+        )
+    }
+
+    // Point of the bounding box of `n` on the line from its center to `to`
+    fn border(&self, n: Node, to: (f64, f64)) -> (f64, f64) {
+        let (dx, dy) = (to.0 - self.x[n], to.1 - self.y[n]);
+        let (w, h) = self.sizes[n];
+        let t = (w / 2.0 / dx.abs()).min(h / 2.0 / dy.abs());
+        if !t.is_finite() {
+            return (self.x[n], self.y[n]);
+        }
+        (self.x[n] + dx * t.min(1.0), self.y[n] + dy * t.min(1.0))
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// SVG presentation attributes of a graphviz style, `fill` is for the nodes that aren't filled
+fn stroke(style: dot::Style, color: &str, fill: &str) -> String {
+    match style {
+        dot::Style::Filled => format!(r#"fill="{color}" stroke="{color}""#),
+        dot::Style::Dashed => format!(r#"fill="{fill}" stroke="{color}" stroke-dasharray="5,3""#),
+        dot::Style::Dotted => format!(r#"fill="{fill}" stroke="{color}" stroke-dasharray="1,3""#),
+        dot::Style::Bold => format!(r#"fill="{fill}" stroke="{color}" stroke-width="3""#),
+        _ => format!(r#"fill="{fill}" stroke="{color}""#),
+    }
+}
+
+fn polygon(points: &[(f64, f64)], attrs: &str) -> String {
+    let points: Vec<_> = points
+        .iter()
+        .map(|(x, y)| format!("{x:.1},{y:.1}"))
+        .collect();
+    format!(r#"<polygon points="{}" {attrs}/>"#, points.join(" "))
+}
+
+fn octagon(x: f64, y: f64, w: f64, h: f64) -> Vec<(f64, f64)> {
+    let c = h.min(w) / 4.0;
+    vec![
+        (x + c, y),
+        (x + w - c, y),
+        (x + w, y + c),
+        (x + w, y + h - c),
+        (x + w - c, y + h),
+        (x + c, y + h),
+        (x, y + h - c),
+        (x, y + c),
+    ]
+}
+
+fn write_shape(svg: &mut String, shape: &str, (x, y, w, h): (f64, f64, f64, f64), attrs: &str) {
+    let shape = match shape {
+        "ellipse" | "circle" => format!(
+            r#"<ellipse cx="{:.1}" cy="{:.1}" rx="{:.1}" ry="{:.1}" {attrs}/>"#,
+            x + w / 2.0,
+            y + h / 2.0,
+            w / 2.0,
+            h / 2.0
+        ),
+        "note" => polygon(
+            &[
+                (x, y),
+                (x + w - 8.0, y),
+                (x + w, y + 8.0),
+                (x + w, y + h),
+                (x, y + h),
+            ],
+            attrs,
+        ),
+        "hexagon" => polygon(
+            &[
+                (x, y + h / 2.0),
+                (x + PADDING, y),
+                (x + w - PADDING, y),
+                (x + w, y + h / 2.0),
+                (x + w - PADDING, y + h),
+                (x + PADDING, y + h),
+            ],
+            attrs,
+        ),
+        // just the label
+        "plaintext" => String::new(),
+        "doubleoctagon" => format!(
+            "{}{}",
+            polygon(&octagon(x, y, w, h), attrs),
+            polygon(&octagon(x + 4.0, y + 4.0, w - 8.0, h - 8.0), attrs)
+        ),
+        _ => format!(r#"<rect x="{x:.1}" y="{y:.1}" width="{w:.1}" height="{h:.1}" {attrs}/>"#),
+    };
+    svg.push_str(&shape);
+}
+
+// Opens the group of a node with its tree data as `data-*` attributes and a tooltip
+fn write_node_start(svg: &mut String, graph: &Graph, n: Node) -> Result<(), std::fmt::Error> {
+    if n >= graph.legend {
+        return write!(
+            svg,
+            r#"<g id="{}" class="legend"><title>{}</title>"#,
+            graph.node_id(n),
+            escape(&graph.nodes[n].0)
+        );
+    }
+
+    let data = NodeData::new(graph, n);
+    write!(svg, r#"<g id="{}" class="node""#, data.id)?;
+    let mut title = vec![];
+    for ((name, _), value) in ATTRIBUTES.iter().zip(data.attributes()) {
+        if let Some(value) = value {
+            write!(svg, r#" data-{name}="{}""#, escape(&value))?;
+            if *name != "color" {
+                title.push(format!("{name}: {value}"));
+            }
+        }
+    }
+    write!(svg, "><title>{}</title>", escape(&title.join("\n")))
+}
+
+fn write_svg(graph: &Graph, layout: &Layout, theme: Theme) -> Result<String, std::fmt::Error> {
+    let mut svg = String::new();
+    let metadata: String = graph
+        .metadata
+        .iter()
+        .map(|(name, value)| format!(r#" data-{name}="{}""#, escape(value)))
+        .collect();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}pt" height="{h:.0}pt" viewBox="0 0 {w:.1} {h:.1}" font-family="monospace" font-size="{FONT_SIZE}" fill="{foreground}" data-graph-id="{id}"{metadata}>"#,
+        w = layout.width,
+        h = layout.height,
+        id = graph.id,
+        foreground = theme.foreground()
+    )?;
+    writeln!(
+        svg,
+        r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="{}"/></marker></defs>"#,
+        theme.foreground()
+    )?;
+    if let Some(background) = theme.background() {
+        writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{background}" stroke="none"/>"#
+        )?;
+    }
+    writeln!(svg, r#"<g id="{}">"#, graph.id)?;
+
+    for edge in graph.edges.iter() {
+        let (source, target) = (edge.0, edge.1);
+        let ((x1, y1), (x2, y2)) = layout.edge_ends(source, target);
+        let color = graph.edge_color(edge).unwrap_or(theme.foreground());
+        let attrs = match graph.edge_style(edge) {
+            dot::Style::Dashed => r#" stroke-dasharray="5,3""#,
+            dot::Style::Bold => r#" stroke-width="3""#,
+            _ => "",
+        };
+        let (source, target) = (graph.node_id(source), graph.node_id(target));
+        write!(
+            svg,
+            r#"<g class="edge" data-source="{source}" data-target="{target}" data-label="{label}"><title>{source} -&gt; {target}</title>"#,
+            label = escape(&edge.2)
+        )?;
+        write!(
+            svg,
+            r#"<line x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}" stroke="{color}"{attrs} marker-end="url(#arrow)"/>"#
+        )?;
+        if !edge.2.is_empty() {
+            writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                (x1 + x2) / 2.0 + 4.0,
+                (y1 + y2) / 2.0,
+                escape(&edge.2)
+            )?;
+        }
+        writeln!(svg, "</g>")?;
+    }
+
+    for n in 0..graph.nodes.len() {
+        let (w, h) = layout.sizes[n];
+        let (x, y) = (layout.x[n] - w / 2.0, layout.y[n] - h / 2.0);
+        write_node_start(&mut svg, graph, n)?;
+        let attrs = stroke(graph.style(n), graph.color(n), theme.node_fill());
+        write_shape(&mut svg, graph.styles[n].shape(), (x, y, w, h), &attrs);
+
+        let label = graph.label(n);
+        let lines: Vec<_> = label.lines().collect();
+        // first baseline, so that the block of lines is vertically centered
+        let top = y + h / 2.0 - (lines.len() as f64 - 1.0) * LINE_HEIGHT / 2.0 + FONT_SIZE / 3.0;
+        svg.push_str(&format!(
+            r#"<text x="{:.1}" y="{top:.1}" text-anchor="middle">"#,
+            layout.x[n]
+        ));
+        for (i, line) in lines.iter().enumerate() {
+            let dy = if i == 0 { 0.0 } else { LINE_HEIGHT };
+            svg.push_str(&format!(
+                r#"<tspan x="{:.1}" dy="{dy}">{}</tspan>"#,
+                layout.x[n],
+                escape(line)
+            ));
+        }
+        svg.push_str("</text></g>\n");
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dot::{build_graph, RenderOptions},
+        render::RenderConfig,
+        verkle::{testing::witness, WitnessTree},
+    };
+
+    #[test]
+    fn test_layout_has_no_overlaps() {
+        let mut keys = vec![];
+        for i in 0..4u8 {
+            let mut key = [0u8; 32];
+            key[0] = i % 2;
+            key[1] = i;
+            key[31] = i * 70;
+            keys.push(key);
+        }
+        let (hint, keyvals) = witness(&keys, &[[5u8; 32]]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let options = RenderOptions {
+            legend: true,
+            ..RenderOptions::default()
+        };
+        let graph = build_graph(&tree, &options);
+
+        let no_overlaps = |layout: &Layout| {
+            for a in 0..graph.nodes.len() {
+                for b in a + 1..graph.nodes.len() {
+                    let apart_x = (layout.x[a] - layout.x[b]).abs()
+                        >= (layout.sizes[a].0 + layout.sizes[b].0) / 2.0;
+                    let apart_y = (layout.y[a] - layout.y[b]).abs()
+                        >= (layout.sizes[a].1 + layout.sizes[b].1) / 2.0;
+                    assert!(apart_x || apart_y, "nodes {a} and {b} overlap");
+                }
+                assert!(layout.x[a] - layout.sizes[a].0 / 2.0 >= 0.0);
+                assert!(layout.y[a] - layout.sizes[a].1 / 2.0 >= 0.0);
+                assert!(layout.x[a] + layout.sizes[a].0 / 2.0 <= layout.width);
+                assert!(layout.y[a] + layout.sizes[a].1 / 2.0 <= layout.height);
+            }
+        };
+
+        let layout = Layout::new(&graph, &LayoutOptions::default());
+        no_overlaps(&layout);
+        for (source, target, _) in graph.edges.iter() {
+            assert!(
+                layout.y[*target] - layout.sizes[*target].1 / 2.0
+                    > layout.y[*source] + layout.sizes[*source].1 / 2.0
+            );
+        }
+
+        let options = LayoutOptions {
+            rankdir: RankDir::Rl,
+            ..LayoutOptions::default()
+        };
+        let layout = Layout::new(&graph, &options);
+        no_overlaps(&layout);
+        for (source, target, _) in graph.edges.iter() {
+            assert!(
+                layout.x[*target] + layout.sizes[*target].0 / 2.0
+                    < layout.x[*source] - layout.sizes[*source].0 / 2.0
+            );
+        }
+
+        let options = LayoutOptions {
+            engine: Engine::Twopi,
+            ..LayoutOptions::default()
+        };
+        no_overlaps(&Layout::new(&graph, &options));
+
+        let renderer = NativeSvg {
+            max_pixels: RenderConfig::default().max_pixels,
+        };
+        let svg = renderer
+            .render(&graph, ImageFormat::Svg, &LayoutOptions::default())
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        for n in 0..graph.nodes.len() {
+            assert!(svg.contains(&format!("<g id=\"{}\" ", graph.node_id(n))));
+        }
+        assert_eq!(svg.matches("<line ").count(), graph.edges.len());
+        assert_eq!(
+            svg.matches("<title>").count(),
+            graph.nodes.len() + graph.edges.len()
+        );
+        // leaves carry their key and decoded value
+        let key = format!(r#" data-key="0x{}""#, hex::encode(keys[0]));
+        let leaf = svg.lines().find(|line| line.contains(&key)).unwrap();
+        assert!(leaf.contains(r#" data-type="leaf""#));
+        assert!(leaf.contains(r#" data-decoded=""#));
+    }
+}
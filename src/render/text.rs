@@ -0,0 +1,163 @@
+// Indented tree for terminals, drawn from the witness tree itself rather than the graph, so
+// it needs no layout. Every line is the edge label, the node type and its shortened data.
+
+use crate::{
+    dot::NodeStyle,
+    export::decode_value,
+    verkle::{commitment_to_hex, NodeId, NodeKind, WitnessTree},
+};
+
+#[derive(Debug, Clone)]
+pub struct TextOptions {
+    /// Plain ASCII branches for terminals without Unicode
+    pub ascii: bool,
+    /// Children of nodes at this depth are counted instead of drawn
+    pub max_depth: Option<usize>,
+    /// Hex digits kept of commitments, stems, keys and values
+    pub hex_width: usize,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            ascii: false,
+            max_depth: None,
+            hex_width: 8,
+        }
+    }
+}
+
+/// Branch, last branch, continued and finished prefixes
+const UNICODE: [&str; 4] = ["├── ", "└── ", "│   ", "    "];
+const ASCII: [&str; 4] = ["|-- ", "`-- ", "|   ", "    "];
+/// Longest decoded value printed, bigger ones are hashes rather than numbers
+const MAX_DECODED: usize = 20;
+
+struct Writer<'a> {
+    tree: &'a WitnessTree,
+    options: &'a TextOptions,
+    branches: [&'static str; 4],
+    out: String,
+}
+
+fn short(bytes: &[u8], options: &TextOptions) -> String {
+    short_hex(&hex::encode(bytes), options)
+}
+
+fn short_hex(hex: &str, options: &TextOptions) -> String {
+    match hex.get(..options.hex_width) {
+        Some(start) if start.len() < hex.len() => {
+            let ellipsis = if options.ascii { "..." } else { "…" };
+            format!("0x{start}{ellipsis}")
+        }
+        _ => format!("0x{hex}"),
+    }
+}
+
+/// Type and shortened data of a node, without its edge label
+pub(crate) fn node_line(tree: &WitnessTree, id: NodeId, options: &TextOptions) -> String {
+    let node = tree.node(id);
+    let style = NodeStyle::of(tree, id).name();
+    let commitment = match &node.commitment {
+        Some(comm) => short_hex(commitment_to_hex(comm).trim_start_matches("0x"), options),
+        None => "-".to_owned(),
+    };
+    match &node.kind {
+        NodeKind::Internal => format!("{style} {commitment}"),
+        NodeKind::Extension { stem } => format!(
+            "{style} stem={} comm={commitment} (present)",
+            short(stem, options)
+        ),
+        NodeKind::OtherStem { stem } => format!(
+            "{style} stem={} comm={commitment} (different_stem)",
+            short(stem, options)
+        ),
+        NodeKind::Empty => format!("{style} (none)"),
+        NodeKind::Suffix { index } => format!("C{index} {commitment}"),
+        NodeKind::Leaf { value, .. } => {
+            let decoded = decode_value(value);
+            match decoded.len() <= MAX_DECODED {
+                true => format!("{style} {} = {decoded}", short(value, options)),
+                false => format!("{style} {}", short(value, options)),
+            }
+        }
+        NodeKind::Absent { key } => format!("{style} key={}", short(key, options)),
+    }
+}
+
+impl Writer<'_> {
+    fn children(&mut self, id: NodeId, prefix: &str, depth: usize) {
+        let children = &self.tree.node(id).children;
+        if self.options.max_depth.is_some_and(|max| depth >= max) {
+            if !children.is_empty() {
+                let line = format!("{prefix}{}+{} more\n", self.branches[1], children.len());
+                self.out.push_str(&line);
+            }
+            return;
+        }
+
+        for (i, child) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            let (branch, next) = match last {
+                true => (self.branches[1], self.branches[3]),
+                false => (self.branches[0], self.branches[2]),
+            };
+            let line = format!(
+                "{prefix}{branch}{}: {}\n",
+                self.tree.node(*child).edge_label(),
+                node_line(self.tree, *child, self.options)
+            );
+            self.out.push_str(&line);
+            self.children(*child, &format!("{prefix}{next}"), depth + 1);
+        }
+    }
+}
+
+/// The tree one node per line, children indented under their parent
+pub(crate) fn text_tree(tree: &WitnessTree, options: &TextOptions) -> String {
+    let mut writer = Writer {
+        tree,
+        options,
+        branches: if options.ascii { ASCII } else { UNICODE },
+        out: String::new(),
+    };
+    if tree.is_empty() {
+        return writer.out;
+    }
+    let root = tree.root();
+    writer.out = format!("{}\n", node_line(tree, root, options));
+    writer.children(root, "", 0);
+    writer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::witness;
+
+    #[test]
+    fn test_text_tree() {
+        let mut value = [0u8; 32];
+        value[0] = 42;
+        let (hint, mut keyvals) = witness(&[[0u8; 32], [1u8; 32]], &[[5u8; 32]]);
+        keyvals.values[0] = Some(value);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+
+        let text = text_tree(&tree, &TextOptions::default());
+        assert_eq!(text.lines().count(), tree.len());
+        assert!(text.starts_with("root 0x"));
+        assert!(text.contains("leaf 0x2a000000… = 42"));
+        assert!(text.contains("(present)"));
+
+        let options = TextOptions {
+            ascii: true,
+            max_depth: Some(1),
+            ..TextOptions::default()
+        };
+        let text = text_tree(&tree, &options);
+        assert!(text.is_ascii());
+        assert!(text.contains("`-- "));
+        assert!(text.contains(" more"));
+        assert!(!text.contains("leaf"));
+    }
+}
@@ -1,87 +1,713 @@
 use crate::{
-    dot::to_dot,
-    types::VerkleReq,
-    verkle::{decode_block, get_rlp, verification},
+    dot::{
+        aggregate_graph, build_graph, diff_graph, heat_color, key_path_graph, simplify, Graph,
+        Highlight, RenderOptions, Simplify,
+    },
+    export,
+    render::{dot_source, preview, thumbnail, ImageFormat, LayoutOptions, Renderer},
+    types::{
+        DiffReq, Format, FormatQuery, GasQuery, ImageQuery, KeyReq, LayoutQuery, Overlay, RangeReq,
+        ThumbnailQuery, VerkleReq, VerkleResp,
+    },
+    verkle::{
+        decode_block, explain_key, get_rlp, proof_stats, range_stats, stem_heatmap, witness_gas,
+    },
+    witness::{verify_witness, Witness, WitnessError},
     Config,
 };
-use actix_web::{http::StatusCode, web, App, HttpResponse, HttpServer};
-use tempfile::tempdir;
-use tokio::process::Command;
-
-async fn get_block_info(info: web::Path<VerkleReq>) -> Result<HttpResponse, crate::error::Error> {
-    let block_number = info.block_number;
-    let block_rlp = get_rlp(block_number).await?;
-    let block = decode_block(block_rlp)?;
+use actix_web::{
+    http::{header, StatusCode},
+    web, App, HttpRequest, HttpResponse, HttpServer, ResponseError,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::collections::BTreeMap;
 
+// Fetches the block and its parent and verifies the block proof against the parent root.
+// Failures are returned as ready-to-send responses.
+async fn fetch_witness(block_number: u64) -> Result<Witness, HttpResponse> {
     if block_number < 2 {
-        return Ok(HttpResponse::build(StatusCode::BAD_REQUEST)
+        return Err(HttpResponse::build(StatusCode::BAD_REQUEST)
             .content_type("text/html")
             .body("Incorrect block_number"));
     }
 
-    let previous_block_rlp = get_rlp(block_number - 1).await?;
-    let previous_block = decode_block(previous_block_rlp)?;
-
-    // println!("current_hash: {}", hex::encode(&block.header.parent_hash));
-    // println!("current_root: {}", hex::encode(&block.header.storage_root));
-    // println!("parent_hash: {}", hex::encode(&previous_block.header.parent_hash));
-    // println!("parent_root: {}", hex::encode(&previous_block.header.storage_root));
-    
-    let parent_root = previous_block.header.storage_root;
-    let keyvals = block_verkle_proof_extractor::keyvals::KeyVals {
-        keys: block.header.keyvals.keys.clone(),
-        values: block.header.keyvals.values.clone(),
-    };
-
-    let dir = tempdir()?;
-    let file_path = dir.path().join("tmp.dot");
-
-    match verification(block, &parent_root) {
-        // FIX: to_dot lead to block :(
-        Ok(val) => match to_dot(&val, &keyvals, &file_path) {
-            Ok(()) => {
-                let image_path = dir.path().join("tmp.svg");
-                let mut child = Command::new("dot")
-                    .arg("-Tsvg")
-                    .arg(&file_path)
-                    .arg("-o")
-                    .arg(&image_path)
-                    .spawn()
-                    .expect("failed to spawn");
-
-                // Await until the command completes
-                let _status = child.wait().await?;
-
-                // sending an image
-                // web::block ?
-                let image_content = web::Bytes::from(std::fs::read(&image_path)?);
-
-                // should we drop files?
-                dir.close()?;
-
-                Ok(HttpResponse::build(StatusCode::OK)
-                    .content_type("image/svg+xml")
-                    .body(image_content))
+    let internal = |err: anyhow::Error| crate::error::Error::from(err).error_response();
+
+    let block_rlp = get_rlp(block_number)
+        .await
+        .map_err(|e| internal(e.into()))?;
+    let previous_block_rlp = get_rlp(block_number - 1)
+        .await
+        .map_err(|e| internal(e.into()))?;
+
+    match web::block(move || verify_witness(block_rlp, previous_block_rlp)).await {
+        Ok(Ok(witness)) => Ok(witness),
+        Ok(Err(WitnessError::Decode(err))) => Err(internal(err)),
+        Ok(Err(WitnessError::Verification(err))) => {
+            tracing::error!("Error : {}", err);
+
+            Err(HttpResponse::build(StatusCode::INTERNAL_SERVER_ERROR)
+                .content_type("text/html")
+                .body("Error while verification"))
+        }
+        Err(err) => Err(internal(err.into())),
+    }
+}
+
+// The witness of the next block holds the pre-state of the keys it touches,
+// which is the post-state of the current block. Fails when there is no next block yet.
+async fn fetch_post_values(
+    block_number: u64,
+) -> Result<BTreeMap<[u8; 32], Option<[u8; 32]>>, anyhow::Error> {
+    let block_rlp = get_rlp(block_number + 1).await?;
+    let block = web::block(move || decode_block(block_rlp)).await??;
+    let keyvals = block.header.keyvals;
+
+    Ok(keyvals.keys.into_iter().zip(keyvals.values).collect())
+}
+
+// Without the post-state only the read costs are charged, which the report tells
+async fn post_values_or_reads(block_number: u64) -> Option<BTreeMap<[u8; 32], Option<[u8; 32]>>> {
+    match fetch_post_values(block_number).await {
+        Ok(values) => Some(values),
+        Err(err) => {
+            tracing::warn!("No post-state for block {block_number}: {err}");
+            None
+        }
+    }
+}
+
+// `?format=` wins over the Accept header, SVG without either
+fn negotiate(req: &HttpRequest, format: Option<Format>) -> Option<Format> {
+    if format.is_some() {
+        return format;
+    }
+    match req.headers().get(header::ACCEPT) {
+        Some(accept) => Format::from_accept(accept.to_str().unwrap_or_default()),
+        None => Some(Format::Svg),
+    }
+}
+
+fn not_acceptable() -> HttpResponse {
+    HttpResponse::build(StatusCode::NOT_ACCEPTABLE)
+        .content_type("text/html")
+        .body("Supported formats: svg, png, pdf, dot, json, base64, graphml, gexf, mermaid, cytoscape")
+}
+
+fn to_data_url(image: &[u8], mime: &str) -> String {
+    format!("data:{mime};base64,{}", STANDARD.encode(image))
+}
+
+fn unsupported(
+    renderer: &dyn Renderer,
+    format: ImageFormat,
+    layout: &LayoutOptions,
+) -> Option<HttpResponse> {
+    let message = if !renderer.supports(format) {
+        format!("{} images need the graphviz backend", format.name())
+    } else if !renderer.supports_engine(layout.engine) {
+        format!(
+            "The {} layout needs the graphviz backend",
+            layout.engine.name()
+        )
+    } else {
+        return None;
+    };
+    Some(
+        HttpResponse::build(StatusCode::NOT_ACCEPTABLE)
+            .content_type("text/html")
+            .body(message),
+    )
+}
+
+// Serializes the graph in one of the formats that are not drawn
+fn export_graph(
+    graph: &Graph,
+    format: Format,
+    layout: &LayoutOptions,
+) -> Result<Vec<u8>, anyhow::Error> {
+    Ok(match format {
+        Format::Dot => dot_source(graph, layout)?,
+        Format::Json => serde_json::to_vec(&graph.to_json())?,
+        Format::Graphml => export::to_graphml(graph)?.into_bytes(),
+        Format::Gexf => export::to_gexf(graph)?.into_bytes(),
+        Format::Mermaid => export::to_mermaid(graph)?.into_bytes(),
+        Format::Cytoscape => serde_json::to_vec(&export::to_cytoscape(graph))?,
+        Format::Svg | Format::Png | Format::Pdf | Format::Base64 => {
+            unreachable!("images are drawn by the renderer")
+        }
+    })
+}
+
+// Builds the graph and renders or serializes it off the actix worker threads
+async fn render_graph(
+    format: Format,
+    layout: LayoutOptions,
+    config: &Config,
+    build: impl FnOnce() -> Graph + Send + 'static,
+) -> Result<HttpResponse, crate::error::Error> {
+    let image_format = match format {
+        Format::Svg | Format::Base64 => Some(ImageFormat::Svg),
+        Format::Png => Some(ImageFormat::Png),
+        Format::Pdf => Some(ImageFormat::Pdf),
+        _ => None,
+    };
+
+    let renderer = config.render.renderer();
+    if let Some(response) =
+        image_format.and_then(|image_format| unsupported(renderer.as_ref(), image_format, &layout))
+    {
+        return Ok(response);
+    }
+
+    let body = web::block(move || {
+        let mut graph = build();
+        graph.label_width = layout.label_width;
+        graph.theme = layout.theme;
+        let Some(image_format) = image_format else {
+            return export_graph(&graph, format, &layout);
+        };
+        let image = renderer.render(&graph, image_format, &layout)?;
+        match format {
+            Format::Base64 => {
+                let image = to_data_url(&image, Format::Svg.mime());
+                Ok(serde_json::to_vec(&VerkleResp { image })?)
+            }
+            _ => Ok(image),
+        }
+    })
+    .await??;
+    Ok(HttpResponse::build(StatusCode::OK)
+        .content_type(format.mime())
+        .body(body))
+}
+
+async fn get_block_info(
+    req: HttpRequest,
+    info: web::Path<VerkleReq>,
+    query: web::Query<ImageQuery>,
+    layout: web::Query<LayoutQuery>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    if let Some(response) = incorrect_layout(&layout) {
+        return Ok(response);
+    }
+    let block_number = info.block_number;
+    let format = match negotiate(&req, query.format) {
+        Some(format) => format,
+        None => return Ok(not_acceptable()),
+    };
+    let witness = match fetch_witness(block_number).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    let mut options = RenderOptions {
+        legend: query.legend,
+        ..RenderOptions::default()
+    };
+    if let Some(highlight) = &query.highlight {
+        match Highlight::parse(highlight) {
+            Ok(highlight) => options.highlight = Some(highlight),
+            Err(err) => {
+                return Ok(HttpResponse::build(StatusCode::BAD_REQUEST)
+                    .content_type("text/html")
+                    .body(format!("Incorrect highlight: {err}")))
+            }
+        }
+    }
+    // the gas of the overlay is computed along with the graph
+    let gas_overlay = match query.overlay {
+        Some(Overlay::Gas) => Some(post_values_or_reads(block_number).await),
+        None => None,
+    };
+
+    let focus = match &query.focus {
+        Some(focus) => match hex::decode(focus.trim_start_matches("0x")) {
+            Ok(path) => Some(path),
+            Err(err) => {
+                return Ok(HttpResponse::build(StatusCode::BAD_REQUEST)
+                    .content_type("text/html")
+                    .body(format!("Incorrect focus: {err}")))
             }
-            Err(err) => Err(err.into()),
         },
+        None => None,
+    };
+    let simplify_options = Simplify {
+        max_depth: query.max_depth,
+        focus,
+        collapse_chains: query.collapse,
+        max_children: query.max_children,
+    };
+
+    let schedule = config.gas;
+    render_graph(
+        format,
+        layout.apply(config.render.layout),
+        &config,
+        move || {
+            if let Some(post_values) = gas_overlay {
+                let report = witness_gas(
+                    &witness.hint,
+                    &witness.keyvals,
+                    post_values.as_ref(),
+                    &schedule,
+                );
+                let totals = report.stem_totals();
+                let max = totals.values().copied().max().unwrap_or_default();
+                options.stem_colors = totals
+                    .into_iter()
+                    .map(|(stem, total)| (stem, heat_color(total, max)))
+                    .collect();
+            }
+            let mut graph = simplify(&build_graph(&witness.tree, &options), &simplify_options);
+            describe_block(&mut graph, block_number, &witness);
+            graph
+        },
+    )
+    .await
+}
+
+fn describe_block(graph: &mut Graph, block_number: u64, witness: &Witness) {
+    graph.id = format!("block_{block_number}");
+    graph.metadata = BTreeMap::from([
+        ("block", block_number.to_string()),
+        ("keys", witness.keyvals.keys.len().to_string()),
+        (
+            "stems",
+            witness.hint.depths_and_ext_by_stem.len().to_string(),
+        ),
+        ("proof-size", witness.proof_size.to_string()),
+    ]);
+}
+
+async fn get_block_thumbnail(
+    info: web::Path<VerkleReq>,
+    query: web::Query<ThumbnailQuery>,
+    layout: web::Query<LayoutQuery>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    if let Some(response) = incorrect_layout(&layout) {
+        return Ok(response);
+    }
+    let thumbnail_config = config.render.thumbnail;
+    let size = (
+        query.width.unwrap_or(thumbnail_config.width),
+        query.height.unwrap_or(thumbnail_config.height),
+    );
+    if [size.0, size.1]
+        .iter()
+        .any(|side| !(1..=thumbnail_config.max_size).contains(side))
+    {
+        return Ok(HttpResponse::build(StatusCode::BAD_REQUEST)
+            .content_type("text/html")
+            .body(format!(
+                "Thumbnail width and height must be between 1 and {}",
+                thumbnail_config.max_size
+            )));
+    }
+    let layout = layout.apply(config.render.layout);
+    let renderer = config.render.renderer();
+    if let Some(response) = unsupported(renderer.as_ref(), ImageFormat::Svg, &layout) {
+        return Ok(response);
+    }
+    let witness = match fetch_witness(info.block_number).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    let block_number = info.block_number;
+    let format = query.format.unwrap_or_default();
+    let image = web::block(move || {
+        let mut graph = build_graph(&witness.tree, &RenderOptions::default());
+        describe_block(&mut graph, block_number, &witness);
+        graph.label_width = layout.label_width;
+        graph.theme = layout.theme;
+        let svg = renderer.render(&graph, ImageFormat::Svg, &layout)?;
+        thumbnail(&svg, size, format)
+    })
+    .await??;
+    Ok(HttpResponse::build(StatusCode::OK)
+        .content_type(format.mime())
+        .body(image))
+}
+
+// Open Graph image for link unfurls, with the block number and key counts over the tree
+async fn get_block_preview(
+    info: web::Path<VerkleReq>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    let layout = config.render.layout;
+    let renderer = config.render.renderer();
+    if let Some(response) = unsupported(renderer.as_ref(), ImageFormat::Svg, &layout) {
+        return Ok(response);
+    }
+    let block_number = info.block_number;
+    let witness = match fetch_witness(block_number).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    let title = format!("Verkle witness of block {block_number}");
+    let subtitle = format!(
+        "{} keys, {} stems, {} bytes of proof",
+        witness.keyvals.keys.len(),
+        witness.hint.depths_and_ext_by_stem.len(),
+        witness.proof_size
+    );
+    let image = web::block(move || {
+        let mut graph = build_graph(&witness.tree, &RenderOptions::default());
+        describe_block(&mut graph, block_number, &witness);
+        graph.label_width = layout.label_width;
+        graph.theme = layout.theme;
+        let svg = renderer.render(&graph, ImageFormat::Svg, &layout)?;
+        preview(&svg, &title, &subtitle)
+    })
+    .await??;
+    Ok(HttpResponse::build(StatusCode::OK)
+        .content_type("image/png")
+        .body(image))
+}
+
+// The viewer is a single page shipped inside the binary, so it works offline
+async fn get_block_view() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html")
+        .body(include_str!("../static/view.html"))
+}
+
+async fn get_block_gas(
+    info: web::Path<VerkleReq>,
+    query: web::Query<GasQuery>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    let block_number = info.block_number;
+    let witness = match fetch_witness(block_number).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    let post_values = post_values_or_reads(block_number).await;
+    let schedule = query.apply(config.gas);
+    let report = web::block(move || {
+        witness_gas(
+            &witness.hint,
+            &witness.keyvals,
+            post_values.as_ref(),
+            &schedule,
+        )
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+async fn get_key_proof(info: web::Path<KeyReq>) -> Result<HttpResponse, crate::error::Error> {
+    let key = match info.key_bytes() {
+        Ok(key) => key,
         Err(err) => {
-            tracing::error!("Error : {}", err);
+            return Ok(HttpResponse::build(StatusCode::BAD_REQUEST)
+                .content_type("text/html")
+                .body(format!("Incorrect key: {err}")))
+        }
+    };
+    let witness = match fetch_witness(info.block_number).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    match explain_key(&witness.tree, &key) {
+        Some(proof) => Ok(HttpResponse::Ok().json(proof)),
+        None => Ok(key_not_found()),
+    }
+}
 
-            Ok(HttpResponse::build(StatusCode::INTERNAL_SERVER_ERROR)
+async fn get_key_image(
+    req: HttpRequest,
+    info: web::Path<KeyReq>,
+    query: web::Query<FormatQuery>,
+    layout: web::Query<LayoutQuery>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    if let Some(response) = incorrect_layout(&layout) {
+        return Ok(response);
+    }
+    let format = match negotiate(&req, query.format) {
+        Some(format) => format,
+        None => return Ok(not_acceptable()),
+    };
+    let key = match info.key_bytes() {
+        Ok(key) => key,
+        Err(err) => {
+            return Ok(HttpResponse::build(StatusCode::BAD_REQUEST)
                 .content_type("text/html")
-                .body("Error while verification"))
+                .body(format!("Incorrect key: {err}")))
+        }
+    };
+    let witness = match fetch_witness(info.block_number).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    let proof = match explain_key(&witness.tree, &key) {
+        Some(proof) => proof,
+        None => return Ok(key_not_found()),
+    };
+
+    let block_number = info.block_number;
+    render_graph(
+        format,
+        layout.apply(config.render.layout),
+        &config,
+        move || {
+            let mut graph = key_path_graph(&proof);
+            graph.id = format!("block_{block_number}_key_{}", hex::encode(key));
+            graph.metadata = BTreeMap::from([
+                ("block", block_number.to_string()),
+                ("key", format!("0x{}", hex::encode(key))),
+            ]);
+            graph
+        },
+    )
+    .await
+}
+
+async fn get_diff_image(
+    req: HttpRequest,
+    info: web::Path<DiffReq>,
+    query: web::Query<FormatQuery>,
+    layout: web::Query<LayoutQuery>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    if let Some(response) = incorrect_layout(&layout) {
+        return Ok(response);
+    }
+    let format = match negotiate(&req, query.format) {
+        Some(format) => format,
+        None => return Ok(not_acceptable()),
+    };
+    let a = match fetch_witness(info.a).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+    let b = match fetch_witness(info.b).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    let (block_a, block_b) = (info.a, info.b);
+    render_graph(
+        format,
+        layout.apply(config.render.layout),
+        &config,
+        move || {
+            let mut graph = diff_graph(&a.tree, &b.tree);
+            graph.id = format!("diff_{block_a}_{block_b}");
+            graph.metadata = BTreeMap::from([
+                ("block-a", block_a.to_string()),
+                ("block-b", block_b.to_string()),
+            ]);
+            graph
+        },
+    )
+    .await
+}
+
+fn key_not_found() -> HttpResponse {
+    HttpResponse::build(StatusCode::NOT_FOUND)
+        .content_type("text/html")
+        .body("Key is not in the block witness")
+}
+
+async fn get_block_stats(
+    info: web::Path<VerkleReq>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    let block_number = info.block_number;
+    let witness = match fetch_witness(block_number).await {
+        Ok(witness) => witness,
+        Err(response) => return Ok(response),
+    };
+
+    let mpt_model = config.mpt;
+    let stats = web::block(move || {
+        proof_stats(
+            block_number,
+            witness.proof_size,
+            &witness.hint,
+            &witness.keyvals,
+            &witness.tree,
+            &mpt_model,
+        )
+    })
+    .await??;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+fn incorrect_layout(layout: &LayoutQuery) -> Option<HttpResponse> {
+    let err = layout.validate().err()?;
+    Some(
+        HttpResponse::build(StatusCode::BAD_REQUEST)
+            .content_type("text/html")
+            .body(format!("Incorrect layout: {err}")),
+    )
+}
+
+fn incorrect_range(range: &RangeReq, config: &Config) -> Option<HttpResponse> {
+    if range.from <= range.to && range.to - range.from < config.server.max_block_range {
+        return None;
+    }
+    Some(
+        HttpResponse::build(StatusCode::BAD_REQUEST)
+            .content_type("text/html")
+            .body(format!(
+                "Incorrect range, at most {} blocks are allowed",
+                config.server.max_block_range
+            )),
+    )
+}
+
+// Witnesses of the blocks `from..=to` and the blocks that failed verification
+async fn fetch_range(range: &RangeReq) -> (Vec<(u64, Witness)>, Vec<u64>) {
+    let mut blocks = vec![];
+    let mut skipped = vec![];
+    for block_number in range.from..=range.to {
+        match fetch_witness(block_number).await {
+            Ok(witness) => blocks.push((block_number, witness)),
+            Err(_) => skipped.push(block_number),
         }
     }
+    (blocks, skipped)
+}
+
+async fn get_range_stats(
+    range: web::Query<RangeReq>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    if let Some(response) = incorrect_range(&range, &config) {
+        return Ok(response);
+    }
+
+    let (witnesses, skipped) = fetch_range(&range).await;
+    let (from, to) = (range.from, range.to);
+    let mpt_model = config.mpt;
+    let stats = web::block(move || {
+        let mut blocks = vec![];
+        for (block_number, witness) in witnesses.iter() {
+            blocks.push(proof_stats(
+                *block_number,
+                witness.proof_size,
+                &witness.hint,
+                &witness.keyvals,
+                &witness.tree,
+                &mpt_model,
+            )?);
+        }
+        Ok::<_, anyhow::Error>(range_stats(from, to, &blocks, skipped))
+    })
+    .await??;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+async fn get_range_tree(
+    req: HttpRequest,
+    range: web::Query<RangeReq>,
+    query: web::Query<FormatQuery>,
+    layout: web::Query<LayoutQuery>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    if let Some(response) = incorrect_layout(&layout) {
+        return Ok(response);
+    }
+    let format = match negotiate(&req, query.format) {
+        Some(format) => format,
+        None => return Ok(not_acceptable()),
+    };
+    if let Some(response) = incorrect_range(&range, &config) {
+        return Ok(response);
+    }
+
+    let (witnesses, _) = fetch_range(&range).await;
+    let (from, to) = (range.from, range.to);
+    render_graph(
+        format,
+        layout.apply(config.render.layout),
+        &config,
+        move || {
+            let trees: Vec<_> = witnesses.iter().map(|(_, witness)| &witness.tree).collect();
+            let mut graph = aggregate_graph(&trees);
+            graph.id = format!("blocks_{from}_{to}");
+            graph.metadata = BTreeMap::from([
+                ("from", from.to_string()),
+                ("to", to.to_string()),
+                ("blocks", trees.len().to_string()),
+            ]);
+            graph
+        },
+    )
+    .await
+}
+
+async fn get_range_heatmap(
+    range: web::Query<RangeReq>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, crate::error::Error> {
+    if let Some(response) = incorrect_range(&range, &config) {
+        return Ok(response);
+    }
+
+    let (witnesses, skipped) = fetch_range(&range).await;
+    let (from, to) = (range.from, range.to);
+    let heatmap = web::block(move || {
+        let blocks: Vec<_> = witnesses
+            .iter()
+            .map(|(block_number, witness)| (*block_number, &witness.keyvals))
+            .collect();
+        stem_heatmap(from, to, &blocks, skipped)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(heatmap))
 }
 
 pub async fn run_http(config: Config) -> std::io::Result<()> {
     let socket_addr = config.server.addr;
+    let config = web::Data::new(config);
 
     tracing::info!("Server is starting at {}", socket_addr);
     HttpServer::new(move || {
         App::new()
+            .app_data(config.clone())
             .service(web::resource("/block/{block_number}").route(web::get().to(get_block_info)))
+            .service(
+                web::resource("/block/{block_number}/view").route(web::get().to(get_block_view)),
+            )
+            .service(
+                web::resource("/block/{block_number}/thumbnail")
+                    .route(web::get().to(get_block_thumbnail)),
+            )
+            .service(
+                web::resource("/block/{block_number}/preview.png")
+                    .route(web::get().to(get_block_preview)),
+            )
+            .service(web::resource("/block/{block_number}/gas").route(web::get().to(get_block_gas)))
+            .service(
+                web::resource("/block/{block_number}/stats").route(web::get().to(get_block_stats)),
+            )
+            .service(
+                web::resource("/block/{block_number}/key/{key}")
+                    .route(web::get().to(get_key_proof)),
+            )
+            .service(
+                web::resource("/block/{block_number}/key/{key}/image")
+                    .route(web::get().to(get_key_image)),
+            )
+            .service(web::resource("/diff/{a}/{b}").route(web::get().to(get_diff_image)))
+            .service(web::resource("/blocks/stats").route(web::get().to(get_range_stats)))
+            .service(web::resource("/blocks/tree").route(web::get().to(get_range_tree)))
+            .service(web::resource("/blocks/heatmap").route(web::get().to(get_range_heatmap)))
     })
     .bind(socket_addr)?
     .run()
@@ -0,0 +1,395 @@
+use crate::{
+    dot::Highlight,
+    export::decode_value,
+    verkle::{decode_block, header_stem, LeafMeaning, NodeId, NodeKind, WitnessTree},
+    witness::{verify_witness, BlockRlp, WitnessError},
+};
+use ratatui::{crossterm::event::KeyCode, widgets::ListState};
+use std::collections::HashSet;
+
+/// Bigger trees start with only the root unfolded
+const EXPAND_ALL_UP_TO: usize = 500;
+const PAGE: usize = 20;
+
+pub(crate) enum Status {
+    Verified { stems: usize, proof_size: usize },
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Pane {
+    Tree,
+    Keys,
+}
+
+pub(crate) struct App {
+    pub block_number: u64,
+    /// None if the block failed verification
+    pub tree: Option<WitnessTree>,
+    pub status: Status,
+    pub keys: Vec<([u8; 32], Option<[u8; 32]>)>,
+    /// Stems of the account headers of the addresses known to be touched, whose keys are decoded
+    header_stems: HashSet<[u8; 31]>,
+    expanded: HashSet<NodeId>,
+    /// Visible tree nodes and their depth, in display order
+    pub rows: Vec<(NodeId, usize)>,
+    pub tree_state: ListState,
+    pub key_state: ListState,
+    pub pane: Pane,
+    /// What is typed after `/`, None when not searching
+    pub search: Option<String>,
+    /// Outcome of the last action, shown instead of the help line
+    pub message: String,
+}
+
+impl App {
+    pub fn new(
+        block_number: u64,
+        tree: Option<WitnessTree>,
+        keys: Vec<([u8; 32], Option<[u8; 32]>)>,
+        addresses: &[[u8; 20]],
+        status: Status,
+    ) -> Self {
+        let expanded = match &tree {
+            Some(tree) if tree.len() <= EXPAND_ALL_UP_TO => (0..tree.len())
+                .filter(|id| !tree.node(*id).children.is_empty())
+                .collect(),
+            Some(tree) if !tree.is_empty() => HashSet::from([tree.root()]),
+            _ => HashSet::new(),
+        };
+        let mut app = App {
+            block_number,
+            tree,
+            status,
+            keys,
+            header_stems: addresses.iter().map(header_stem).collect(),
+            expanded,
+            rows: vec![],
+            tree_state: ListState::default(),
+            key_state: ListState::default(),
+            pane: Pane::Tree,
+            search: None,
+            message: String::new(),
+        };
+        app.update_rows();
+        app.tree_state.select((!app.rows.is_empty()).then_some(0));
+        app.key_state.select((!app.keys.is_empty()).then_some(0));
+        app
+    }
+
+    /// Verifies the block, a block failing verification still shows its keys
+    pub fn load(block: BlockRlp) -> Self {
+        match verify_witness(block.rlp.clone(), block.parent_rlp) {
+            Ok(witness) => {
+                let status = Status::Verified {
+                    stems: witness.hint.depths_and_ext_by_stem.len(),
+                    proof_size: witness.proof_size,
+                };
+                let keyvals = witness.keyvals;
+                let keys = keyvals.keys.into_iter().zip(keyvals.values).collect();
+                App::new(
+                    block.number,
+                    Some(witness.tree),
+                    keys,
+                    &witness.addresses,
+                    status,
+                )
+            }
+            Err(err) => {
+                let status = match err {
+                    WitnessError::Decode(err) => Status::Failed(format!("decoding failed: {err}")),
+                    WitnessError::Verification(err) => {
+                        Status::Failed(format!("verification failed: {err}"))
+                    }
+                };
+                let (keys, addresses) = decode_block(block.rlp)
+                    .map(|block| {
+                        let addresses = block.addresses();
+                        let keyvals = block.header.keyvals;
+                        let keys = keyvals.keys.into_iter().zip(keyvals.values).collect();
+                        (keys, addresses)
+                    })
+                    .unwrap_or_default();
+                App::new(block.number, None, keys, &addresses, status)
+            }
+        }
+    }
+
+    fn update_rows(&mut self) {
+        self.rows.clear();
+        let Some(tree) = &self.tree else { return };
+        if tree.is_empty() {
+            return;
+        }
+        let mut stack = vec![(tree.root(), 0)];
+        while let Some((id, depth)) = stack.pop() {
+            self.rows.push((id, depth));
+            if self.expanded.contains(&id) {
+                for child in tree.node(id).children.iter().rev() {
+                    stack.push((*child, depth + 1));
+                }
+            }
+        }
+    }
+
+    pub fn is_expanded(&self, id: NodeId) -> bool {
+        self.expanded.contains(&id)
+    }
+
+    /// Tree node of the selected row
+    pub fn selected(&self) -> Option<NodeId> {
+        let row = self.tree_state.selected()?;
+        self.rows.get(row).map(|(id, _)| *id)
+    }
+
+    /// Meaning and value of a key of the block, decoded if it is a number.
+    /// Only keys of known account header stems have a meaning, others show their suffix.
+    pub fn describe_key(&self, index: usize) -> String {
+        let (key, value) = &self.keys[index];
+        let meaning = self
+            .header_stems
+            .contains(&key[..31])
+            .then(|| LeafMeaning::of(key));
+        let value = match value {
+            Some(value) if meaning.is_some_and(LeafMeaning::is_number) => decode_value(value),
+            Some(value) => format!("0x{}", hex::encode(value)),
+            None => "absent".to_owned(),
+        };
+        match meaning {
+            Some(meaning) => format!("{meaning} = {value}"),
+            None => format!("suffix {} = {value}", key[31]),
+        }
+    }
+
+    fn select_node(&mut self, id: NodeId) {
+        let Some(tree) = &self.tree else { return };
+        let path = tree.path_to(id);
+        self.expanded.extend(&path[..path.len() - 1]);
+        self.update_rows();
+        self.tree_state
+            .select(self.rows.iter().position(|(row, _)| *row == id));
+        self.pane = Pane::Tree;
+    }
+
+    // Selects the node proving a key or the extension of a stem or of the header of an address
+    fn jump(&mut self, query: &str) {
+        let highlight = Highlight::parse(query);
+        // the keys of a searched address are decoded from now on
+        if let Ok(Highlight::Address { stem, .. }) = &highlight {
+            self.header_stems.insert(*stem);
+        }
+        let Some(tree) = &self.tree else {
+            self.message = "The block has no verified tree".to_owned();
+            return;
+        };
+        let found = match highlight {
+            Ok(Highlight::Key(key)) => tree.find_key(&key),
+            Ok(Highlight::Stem(target) | Highlight::Address { stem: target, .. }) => {
+                tree.nodes().iter().position(|node| {
+                    matches!(&node.kind, NodeKind::Extension { stem } | NodeKind::OtherStem { stem } if *stem == target)
+                })
+            }
+            Err(err) => {
+                self.message = err.to_string();
+                return;
+            }
+        };
+        match found {
+            Some(id) => {
+                self.select_node(id);
+                self.message = format!("Found {query}");
+            }
+            None => self.message = format!("{query} is not in the witness"),
+        }
+    }
+
+    fn move_selection(&mut self, down: bool, by: usize) {
+        let (state, len) = match self.pane {
+            Pane::Tree => (&mut self.tree_state, self.rows.len()),
+            Pane::Keys => (&mut self.key_state, self.keys.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0);
+        let next = match down {
+            true => (current + by).min(len - 1),
+            false => current.saturating_sub(by),
+        };
+        state.select(Some(next));
+    }
+
+    fn fold(&mut self, expand: bool) {
+        let (Some(id), Some(tree)) = (self.selected(), &self.tree) else {
+            return;
+        };
+        let node = tree.node(id);
+        match (expand, self.expanded.contains(&id)) {
+            (true, false) if !node.children.is_empty() => {
+                self.expanded.insert(id);
+            }
+            (false, true) => {
+                self.expanded.remove(&id);
+            }
+            // folding a folded node goes up to its parent
+            (false, false) => {
+                if let Some(parent) = node.parent {
+                    self.select_node(parent);
+                }
+                return;
+            }
+            _ => return,
+        }
+        self.update_rows();
+    }
+
+    /// Returns false when the user quits
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(search) = &mut self.search {
+            match code {
+                KeyCode::Char(c) => search.push(c),
+                KeyCode::Backspace => {
+                    search.pop();
+                }
+                KeyCode::Enter => {
+                    let query = search.trim().to_owned();
+                    self.search = None;
+                    self.jump(&query);
+                }
+                KeyCode::Esc => self.search = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        self.message.clear();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => self.search = Some(String::new()),
+            KeyCode::Tab => {
+                self.pane = match self.pane {
+                    Pane::Tree => Pane::Keys,
+                    Pane::Keys => Pane::Tree,
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(true, 1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(false, 1),
+            KeyCode::PageDown => self.move_selection(true, PAGE),
+            KeyCode::PageUp => self.move_selection(false, PAGE),
+            KeyCode::Right | KeyCode::Char('l') if self.pane == Pane::Tree => self.fold(true),
+            KeyCode::Left | KeyCode::Char('h') if self.pane == Pane::Tree => self.fold(false),
+            KeyCode::Enter | KeyCode::Char(' ') => match self.pane {
+                Pane::Tree => {
+                    let expanded = self.selected().is_some_and(|id| self.is_expanded(id));
+                    self.fold(!expanded);
+                }
+                Pane::Keys => {
+                    if let Some(index) = self.key_state.selected() {
+                        self.jump(&hex::encode(self.keys[index].0));
+                    }
+                }
+            },
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::{header_stem, testing::witness};
+
+    #[test]
+    fn test_navigation() {
+        let mut balance = [0u8; 32];
+        balance[31] = 1;
+        let mut address = [0u8; 20];
+        address[..3].copy_from_slice(&[1, 2, 3]);
+        let header = header_stem(&address);
+        let mut account = [0u8; 32];
+        account[..31].copy_from_slice(&header);
+        let (hint, keyvals) = witness(&[[0u8; 32], balance, [7u8; 32], account], &[]);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let keys = keyvals.keys.iter().copied().zip(keyvals.values.clone());
+        let status = Status::Verified {
+            stems: 2,
+            proof_size: 0,
+        };
+        let mut app = App::new(9, Some(tree), keys.collect(), &[], status);
+        let all = app.rows.len();
+        assert_eq!(all, app.tree.as_ref().unwrap().len());
+
+        // folding the root hides everything else
+        assert!(app.handle_key(KeyCode::Left));
+        assert_eq!(app.rows.len(), 1);
+        assert!(app.handle_key(KeyCode::Right));
+
+        // jumping unfolds the way to the leaf
+        app.handle_key(KeyCode::Left);
+        for c in format!("/0x{}", hex::encode([7u8; 32])).chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+        let leaf = app.selected().unwrap();
+        assert_eq!(
+            app.tree.as_ref().unwrap().node(leaf).key(),
+            Some(&[7u8; 32])
+        );
+
+        // an address jumps to the extension of its header stem
+        for c in format!("/0x{}", hex::encode(address)).chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+        assert!(app.message.starts_with("Found"));
+        let ext = app.selected().unwrap();
+        assert_eq!(
+            app.tree.as_ref().unwrap().node(ext).kind,
+            NodeKind::Extension { stem: header }
+        );
+
+        // the key list jumps to the leaf of the selected key
+        app.handle_key(KeyCode::Tab);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.pane, Pane::Tree);
+        let leaf = app.selected().unwrap();
+        assert_eq!(app.tree.as_ref().unwrap().node(leaf).key(), Some(&balance));
+
+        assert!(!app.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_describe_key() {
+        let mut address = [0u8; 20];
+        address[..3].copy_from_slice(&[1, 2, 3]);
+        let mut value = [0u8; 32];
+        value[0] = 42;
+        let mut balance = [0u8; 32];
+        balance[..31].copy_from_slice(&header_stem(&address));
+        balance[31] = 1;
+        // the same suffix in a storage or code stem is not a balance
+        let mut other = [0u8; 32];
+        other[31] = 1;
+        let keys = vec![(balance, Some(value)), (other, Some(value)), (other, None)];
+        let status = Status::Failed("verification failed".to_owned());
+
+        let app = App::new(9, None, keys.clone(), &[address], status);
+        assert_eq!(app.describe_key(0), "balance = 42");
+        let raw = format!("suffix 1 = 0x2a{}", "00".repeat(31));
+        assert_eq!(app.describe_key(1), raw);
+        assert_eq!(app.describe_key(2), "suffix 1 = absent");
+
+        // searching an address decodes the keys of its header
+        let status = Status::Failed("verification failed".to_owned());
+        let mut app = App::new(9, None, keys, &[], status);
+        assert_eq!(app.describe_key(0), raw);
+        for c in format!("/0x{}", hex::encode(address)).chars() {
+            app.handle_key(KeyCode::Char(c));
+        }
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.describe_key(0), "balance = 42");
+        assert_eq!(app.describe_key(1), raw);
+    }
+}
@@ -0,0 +1,31 @@
+// Terminal explorer of a block witness: the tree with foldable nodes, the keys of the block
+// with what they hold and the verification status, drawn with ratatui.
+
+mod app;
+mod ui;
+
+pub(crate) use app::App;
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyEventKind},
+    DefaultTerminal,
+};
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), anyhow::Error> {
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Runs until the user quits, the terminal is restored even on errors
+pub(crate) fn run(mut app: App) -> Result<(), anyhow::Error> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,160 @@
+use super::app::{App, Pane, Status};
+use crate::{
+    dot::NodeStyle,
+    render::{node_line, TextOptions},
+    verkle::{commitment_to_hex, NodeKind},
+};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+const HELP: &str =
+    "↑↓ move  ←→ fold  enter toggle/jump  tab switch pane  / jump to key, stem or address  q quit";
+
+fn color(style: NodeStyle) -> Color {
+    match style {
+        NodeStyle::Root | NodeStyle::Internal => Color::Blue,
+        NodeStyle::Extension => Color::Yellow,
+        NodeStyle::Suffix => Color::Cyan,
+        NodeStyle::Leaf | NodeStyle::ZeroLeaf => Color::Green,
+        NodeStyle::Absent | NodeStyle::OtherStem => Color::Red,
+        NodeStyle::Empty | NodeStyle::Summary => Color::DarkGray,
+    }
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(title);
+    match focused {
+        true => block.border_style(Style::new().fg(Color::Cyan)),
+        false => block,
+    }
+}
+
+fn draw_tree(frame: &mut Frame, app: &mut App, area: Rect) {
+    let options = TextOptions::default();
+    let items: Vec<ListItem> = match &app.tree {
+        Some(tree) => app
+            .rows
+            .iter()
+            .map(|(id, depth)| {
+                let node = tree.node(*id);
+                let marker = match (node.children.is_empty(), app.is_expanded(*id)) {
+                    (true, _) => "  ",
+                    (false, true) => "▾ ",
+                    (false, false) => "▸ ",
+                };
+                let label = match node.parent {
+                    Some(_) => format!("{}: ", node.edge_label()),
+                    None => String::new(),
+                };
+                let line = format!(
+                    "{}{marker}{label}{}",
+                    "  ".repeat(*depth),
+                    node_line(tree, *id, &options)
+                );
+                ListItem::new(line).fg(color(NodeStyle::of(tree, *id)))
+            })
+            .collect(),
+        None => vec![],
+    };
+    let list = List::new(items)
+        .block(pane("Witness tree", app.pane == Pane::Tree))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.tree_state);
+}
+
+// Verification of the block and the full data of the selected node
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let mut lines = vec![match &app.status {
+        Status::Verified { stems, proof_size } => Line::from(format!(
+            "block {}: verified, {} keys, {stems} stems, {proof_size} bytes of proof",
+            app.block_number,
+            app.keys.len()
+        ))
+        .fg(Color::Green),
+        Status::Failed(err) => {
+            Line::from(format!("block {}: {err}", app.block_number)).fg(Color::Red)
+        }
+    }];
+
+    if let (Some(tree), Some(id)) = (&app.tree, app.selected()) {
+        let node = tree.node(id);
+        lines.push(Line::default());
+        lines.push(Line::from(format!(
+            "type: {}",
+            NodeStyle::of(tree, id).name()
+        )));
+        let path: Vec<String> = tree
+            .path_to(id)
+            .iter()
+            .skip(1)
+            .map(|id| tree.node(*id).edge_label())
+            .collect();
+        lines.push(Line::from(format!("path: /{}", path.join("/"))));
+        if let Some(comm) = &node.commitment {
+            lines.push(Line::from(format!(
+                "commitment: {}",
+                commitment_to_hex(comm)
+            )));
+        }
+        match &node.kind {
+            NodeKind::Extension { stem } | NodeKind::OtherStem { stem } => {
+                lines.push(Line::from(format!("stem: 0x{}", hex::encode(stem))));
+            }
+            NodeKind::Leaf { key, value } => {
+                lines.push(Line::from(format!("key: 0x{}", hex::encode(key))));
+                lines.push(Line::from(format!("value: 0x{}", hex::encode(value))));
+            }
+            NodeKind::Absent { key } => {
+                lines.push(Line::from(format!("key: 0x{}", hex::encode(key))));
+            }
+            _ => {}
+        }
+    }
+
+    let status = Paragraph::new(lines)
+        .block(pane("Status", false))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(status, area);
+}
+
+fn draw_keys(frame: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = (0..app.keys.len())
+        .map(|i| {
+            let key = hex::encode(app.keys[i].0);
+            ListItem::new(format!(
+                "0x{}…{} {}",
+                &key[..8],
+                &key[62..],
+                app.describe_key(i)
+            ))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane("Keys", app.pane == Pane::Keys))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut app.key_state);
+}
+
+pub(crate) fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+    let [tree, side] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main);
+    let [status, keys] = Layout::vertical([Constraint::Length(12), Constraint::Min(3)]).areas(side);
+
+    draw_tree(frame, app, tree);
+    draw_status(frame, app, status);
+    draw_keys(frame, app, keys);
+
+    let footer_line = match (&app.search, app.message.is_empty()) {
+        (Some(search), _) => Line::from(format!("/{search}█")),
+        (None, false) => Line::from(app.message.as_str()).fg(Color::Yellow),
+        (None, true) => Line::from(HELP).fg(Color::DarkGray),
+    };
+    frame.render_widget(Paragraph::new(footer_line), footer);
+}
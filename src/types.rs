@@ -1,14 +1,207 @@
-use serde::{Deserialize, Serialize};
+use crate::{
+    render::{Engine, LayoutOptions, RankDir, RasterFormat, Theme},
+    verkle::GasSchedule,
+};
+use serde::{Deserialize, Serialize, Serializer};
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
 pub struct VerkleReq {
     pub block_number: u64,
 }
 
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct KeyReq {
+    pub block_number: u64,
+    pub key: String,
+}
+
+impl KeyReq {
+    pub fn key_bytes(&self) -> Result<[u8; 32], anyhow::Error> {
+        let key = hex::decode(self.key.trim_start_matches("0x"))?;
+        key.try_into()
+            .map_err(|_| anyhow::anyhow!("Key must be 32 bytes long"))
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct DiffReq {
+    pub a: u64,
+    pub b: u64,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct RangeReq {
+    pub from: u64,
+    pub to: u64,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Overlay {
+    Gas,
+}
+
+/// Output of the image endpoints
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Svg,
+    Png,
+    Pdf,
+    /// Graphviz source, for clients doing their own layout
+    Dot,
+    /// Nodes and edges with their labels and styles
+    Json,
+    /// `VerkleResp` with the SVG image as a base64 data URL
+    Base64,
+    Graphml,
+    /// Gephi's format
+    Gexf,
+    /// Flowchart for Markdown docs
+    Mermaid,
+    /// Cytoscape.js elements JSON
+    Cytoscape,
+}
+
+impl Format {
+    pub fn mime(self) -> &'static str {
+        match self {
+            Format::Svg => "image/svg+xml",
+            Format::Png => "image/png",
+            Format::Pdf => "application/pdf",
+            Format::Dot => "text/vnd.graphviz",
+            Format::Json | Format::Base64 | Format::Cytoscape => "application/json",
+            Format::Graphml => "application/graphml+xml",
+            Format::Gexf => "application/gexf+xml",
+            Format::Mermaid => "text/vnd.mermaid",
+        }
+    }
+
+    /// The most preferred format of an `Accept` header, None if none of them is supported
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        let mut ranges: Vec<(&str, f32)> = accept
+            .split(',')
+            .map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let mime = parts.next().unwrap_or_default();
+                let quality = parts
+                    .find_map(|param| param.strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (mime, quality)
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+        // stable, so equally preferred types keep their order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges.into_iter().find_map(|(mime, _)| match mime {
+            "image/svg+xml" | "image/*" | "*/*" => Some(Format::Svg),
+            "image/png" => Some(Format::Png),
+            "application/pdf" => Some(Format::Pdf),
+            "text/vnd.graphviz" => Some(Format::Dot),
+            "application/json" => Some(Format::Json),
+            "application/graphml+xml" => Some(Format::Graphml),
+            "application/gexf+xml" => Some(Format::Gexf),
+            "text/vnd.mermaid" => Some(Format::Mermaid),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FormatQuery {
+    pub format: Option<Format>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageQuery {
+    pub format: Option<Format>,
+    pub overlay: Option<Overlay>,
+    /// Key or stem whose path is highlighted
+    pub highlight: Option<String>,
+    #[serde(default)]
+    pub legend: bool,
+    pub max_depth: Option<usize>,
+    /// Stem or key prefix in hex
+    pub focus: Option<String>,
+    /// Collapse chains of single-child internal nodes
+    #[serde(default)]
+    pub collapse: bool,
+    pub max_children: Option<usize>,
+}
+
+/// Per-request overrides of the configured gas schedule
+#[derive(Debug, Deserialize)]
+pub struct GasQuery {
+    pub witness_branch_cost: Option<u64>,
+    pub witness_chunk_cost: Option<u64>,
+    pub subtree_edit_cost: Option<u64>,
+    pub chunk_edit_cost: Option<u64>,
+    pub chunk_fill_cost: Option<u64>,
+}
+
+impl GasQuery {
+    pub fn apply(&self, schedule: GasSchedule) -> GasSchedule {
+        GasSchedule {
+            witness_branch_cost: self
+                .witness_branch_cost
+                .unwrap_or(schedule.witness_branch_cost),
+            witness_chunk_cost: self
+                .witness_chunk_cost
+                .unwrap_or(schedule.witness_chunk_cost),
+            subtree_edit_cost: self.subtree_edit_cost.unwrap_or(schedule.subtree_edit_cost),
+            chunk_edit_cost: self.chunk_edit_cost.unwrap_or(schedule.chunk_edit_cost),
+            chunk_fill_cost: self.chunk_fill_cost.unwrap_or(schedule.chunk_fill_cost),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ThumbnailQuery {
+    pub format: Option<RasterFormat>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// Per-request overrides of the configured layout
+#[derive(Debug, Deserialize)]
+pub struct LayoutQuery {
+    pub engine: Option<Engine>,
+    pub rankdir: Option<RankDir>,
+    /// Inches between nodes, at most `MAX_NODESEP`
+    pub nodesep: Option<f64>,
+    pub label_width: Option<usize>,
+    pub theme: Option<Theme>,
+}
+
+/// Wider gaps only blow up the image size
+pub const MAX_NODESEP: f64 = 10.0;
+
+impl LayoutQuery {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        match self.nodesep {
+            Some(nodesep) if !(0.0..=MAX_NODESEP).contains(&nodesep) => Err(anyhow::anyhow!(
+                "nodesep must be between 0 and {MAX_NODESEP}, got {nodesep}"
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn apply(&self, layout: LayoutOptions) -> LayoutOptions {
+        LayoutOptions {
+            engine: self.engine.unwrap_or(layout.engine),
+            rankdir: self.rankdir.unwrap_or(layout.rankdir),
+            nodesep: self.nodesep.unwrap_or(layout.nodesep),
+            label_width: self.label_width.or(layout.label_width),
+            theme: self.theme.unwrap_or(layout.theme),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct VerkleResp {
-    #[allow(dead_code)]
-    // pub image: Vec<u8>
+    /// `data:image/svg+xml;base64,...`
     pub image: String,
 }
 
@@ -20,3 +213,51 @@ pub struct RPCResp {
     pub result: String,
     id: String,
 }
+
+/// Serializes bytes as a 0x-prefixed hex string
+pub fn serialize_hex<S, T>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: AsRef<[u8]>,
+{
+    serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_accept() {
+        assert_eq!(Format::from_accept("image/png"), Some(Format::Png));
+        assert_eq!(
+            Format::from_accept("text/html, application/json;q=0.9, */*;q=0.8"),
+            Some(Format::Json)
+        );
+        assert_eq!(
+            Format::from_accept("image/svg+xml;q=0.5, application/pdf"),
+            Some(Format::Pdf)
+        );
+        assert_eq!(Format::from_accept("*/*"), Some(Format::Svg));
+        assert_eq!(Format::from_accept("text/html"), None);
+        assert_eq!(Format::from_accept("image/png;q=0"), None);
+    }
+
+    #[test]
+    fn test_layout_query_validate() {
+        let query = |nodesep| LayoutQuery {
+            engine: None,
+            rankdir: None,
+            nodesep,
+            label_width: None,
+            theme: None,
+        };
+        assert!(query(None).validate().is_ok());
+        assert!(query(Some(0.0)).validate().is_ok());
+        assert!(query(Some(MAX_NODESEP)).validate().is_ok());
+        assert!(query(Some(-0.5)).validate().is_err());
+        assert!(query(Some(10.5)).validate().is_err());
+        assert!(query(Some(f64::NAN)).validate().is_err());
+        assert!(query(Some(f64::INFINITY)).validate().is_err());
+    }
+}
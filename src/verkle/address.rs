@@ -0,0 +1,60 @@
+// Tree keys of accounts as laid out by EIP-6800: the key is a Pedersen hash of the address
+// and the tree index, committed with the same CRS as the tree nodes.
+// https://eips.ethereum.org/EIPS/eip-6800
+
+use ark_serialize::CanonicalSerialize;
+use verkle_trie::{
+    committer::{test::TestCommitter, Committer},
+    Fr,
+};
+
+/// First scalar of the hash, 2 + 256 * input length
+const HASH_MARKER: u128 = 2 + 256 * 64;
+
+/// Stem of the account header (version, balance, nonce, code hash and size) of an address,
+/// `get_tree_key(address, 0, 0)` without its last byte
+pub fn header_stem(address: &[u8; 20]) -> [u8; 31] {
+    // the address padded to 32 bytes, then the little-endian tree index, 0 for the header
+    let mut input = [0u8; 64];
+    input[12..32].copy_from_slice(address);
+    let scalars: Vec<Fr> = std::iter::once(HASH_MARKER)
+        .chain(
+            input
+                .chunks(16)
+                .map(|chunk| u128::from_le_bytes(chunk.try_into().unwrap())),
+        )
+        .map(Fr::from)
+        .collect();
+
+    let mut hash = vec![];
+    let _res = CanonicalSerialize::serialize(&TestCommitter.commit_lagrange(&scalars), &mut hash);
+    hash[..31].try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_stem() {
+        // coinbases and a recipient of the condrieu test blocks, with the stems of their keys
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000",
+                "bf101a6e1c8e83c11bd203a582c7981b91097ec55cbd344ce09005c1f26d19",
+            ),
+            (
+                "0102030000000000000000000000000000000000",
+                "cac9a3e8dd152c9b5f8abcd254f1abe57d4acde35cfe0f919b43e6f0930714",
+            ),
+            (
+                "f97e180c050e5ab072211ad2c213eb5aee4df134",
+                "8dc286880de0cc507d96583b7c4c2b2b25239e58f8e67509b32edb5bbf293c",
+            ),
+        ];
+        for (address, stem) in vectors {
+            let address: [u8; 20] = hex::decode(address).unwrap().try_into().unwrap();
+            assert_eq!(hex::encode(header_stem(&address)), stem);
+        }
+    }
+}
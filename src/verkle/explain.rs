@@ -0,0 +1,188 @@
+use crate::{
+    types::serialize_hex,
+    verkle::{commitment_to_hex, NodeKind, WitnessTree},
+};
+use serde::{Serialize, Serializer};
+use verkle_trie::proof::ExtPresent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtensionStatus {
+    Present,
+    DifferentStem,
+    None,
+}
+
+impl From<ExtPresent> for ExtensionStatus {
+    fn from(ext: ExtPresent) -> Self {
+        match ext {
+            ExtPresent::Present => ExtensionStatus::Present,
+            ExtPresent::DifferentStem => ExtensionStatus::DifferentStem,
+            ExtPresent::None => ExtensionStatus::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitmentKind {
+    Internal,
+    Extension,
+    /// C1 (suffixes 0..128) or C2 (suffixes 128..256) of an extension
+    Suffix,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PathCommitment {
+    pub kind: CommitmentKind,
+    #[serde(serialize_with = "serialize_hex")]
+    pub path: Vec<u8>,
+    pub commitment: String,
+}
+
+/// How the witness proves the value of a single key
+#[derive(Debug, Serialize)]
+pub struct KeyProof {
+    #[serde(serialize_with = "serialize_hex")]
+    pub key: [u8; 32],
+    #[serde(serialize_with = "serialize_hex")]
+    pub stem: [u8; 31],
+    pub depth: u8,
+    pub extension: ExtensionStatus,
+    /// The stem found in place of the key's stem, for proofs of absence
+    #[serde(serialize_with = "serialize_opt_hex")]
+    pub other_stem: Option<[u8; 31]>,
+    #[serde(serialize_with = "serialize_opt_hex")]
+    pub value: Option<[u8; 32]>,
+    /// Commitments from the root down to the key's stem
+    pub commitments: Vec<PathCommitment>,
+}
+
+fn serialize_opt_hex<S, T>(bytes: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: AsRef<[u8]>,
+{
+    match bytes {
+        Some(bytes) => serialize_hex(bytes, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Explains the proof of `key`, None if the key is not part of the witness
+pub fn explain_key(tree: &WitnessTree, key: &[u8; 32]) -> Option<KeyProof> {
+    let node = tree.find_key(key)?;
+    let mut stem = [0u8; 31];
+    stem.copy_from_slice(&key[..31]);
+
+    let mut depth = 0;
+    let mut extension = ExtensionStatus::Present;
+    let mut other_stem = None;
+    let mut value = None;
+    let mut commitments = vec![];
+
+    for id in tree.path_to(node) {
+        let node = tree.node(id);
+        let kind = match &node.kind {
+            NodeKind::Internal => CommitmentKind::Internal,
+            NodeKind::Extension { stem } => {
+                depth = node.path.len();
+                other_stem = Some(*stem);
+                CommitmentKind::Extension
+            }
+            NodeKind::OtherStem { stem } => {
+                depth = node.path.len();
+                other_stem = Some(*stem);
+                CommitmentKind::Extension
+            }
+            NodeKind::Suffix { .. } => CommitmentKind::Suffix,
+            NodeKind::Empty => {
+                depth = node.path.len();
+                extension = ExtensionStatus::None;
+                continue;
+            }
+            NodeKind::Leaf { value: v, .. } => {
+                value = Some(*v);
+                continue;
+            }
+            NodeKind::Absent { .. } => {
+                // absent keys of a present stem sit under a suffix commitment,
+                // those of another stem hang directly off its extension
+                let parent = node.parent.map(|parent| &tree.node(parent).kind);
+                if !matches!(parent, Some(NodeKind::Suffix { .. })) {
+                    extension = ExtensionStatus::DifferentStem;
+                }
+                continue;
+            }
+        };
+
+        if let Some(comm) = &node.commitment {
+            commitments.push(PathCommitment {
+                kind,
+                path: node.path.clone(),
+                commitment: commitment_to_hex(comm),
+            });
+        }
+    }
+
+    if extension != ExtensionStatus::DifferentStem {
+        other_stem = None;
+    }
+
+    Some(KeyProof {
+        key: *key,
+        stem,
+        depth: depth as u8,
+        extension,
+        other_stem,
+        value,
+        commitments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::witness;
+
+    fn tree(present: &[[u8; 32]], absent: &[[u8; 32]]) -> WitnessTree {
+        let (hint, keyvals) = witness(present, absent);
+        WitnessTree::from_hint(&hint, &keyvals)
+    }
+
+    #[test]
+    fn test_explain_present_key() {
+        let mut sibling = [0u8; 32];
+        sibling[0] = 1;
+        let tree = tree(&[[0u8; 32], sibling], &[]);
+
+        let proof = explain_key(&tree, &[0u8; 32]).expect("key in witness");
+
+        assert_eq!(proof.extension, ExtensionStatus::Present);
+        assert_eq!(proof.depth, 1);
+        assert_eq!(proof.value, Some([1u8; 32]));
+        let kinds: Vec<_> = proof.commitments.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                CommitmentKind::Internal,
+                CommitmentKind::Extension,
+                CommitmentKind::Suffix
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain_absent_key() {
+        let mut other = [0u8; 32];
+        other[1] = 1;
+        let tree = tree(&[[0u8; 32]], &[other]);
+
+        let proof = explain_key(&tree, &other).expect("key in witness");
+
+        assert_eq!(proof.extension, ExtensionStatus::DifferentStem);
+        assert_eq!(proof.other_stem, Some([0u8; 31]));
+        assert_eq!(proof.value, None);
+        assert!(explain_key(&tree, &[2u8; 32]).is_none());
+    }
+}
@@ -0,0 +1,205 @@
+// Witness gas accounting as described in EIP-4762
+// https://eips.ethereum.org/EIPS/eip-4762
+
+use crate::types::serialize_hex;
+use block_verkle_proof_extractor::keyvals::KeyVals;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use verkle_trie::proof::UpdateHint;
+
+/// Costs charged for witness accesses. Defaults are the EIP-4762 values,
+/// every field can be overridden to evaluate other proposals.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GasSchedule {
+    pub witness_branch_cost: u64,
+    pub witness_chunk_cost: u64,
+    pub subtree_edit_cost: u64,
+    pub chunk_edit_cost: u64,
+    pub chunk_fill_cost: u64,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self {
+            witness_branch_cost: 1900,
+            witness_chunk_cost: 200,
+            subtree_edit_cost: 3000,
+            chunk_edit_cost: 500,
+            chunk_fill_cost: 6200,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct GasCost {
+    pub branch_read: u64,
+    pub chunk_read: u64,
+    pub subtree_edit: u64,
+    pub chunk_edit: u64,
+    pub chunk_fill: u64,
+    pub total: u64,
+}
+
+impl GasCost {
+    fn add(&mut self, other: &GasCost) {
+        self.branch_read += other.branch_read;
+        self.chunk_read += other.chunk_read;
+        self.subtree_edit += other.subtree_edit;
+        self.chunk_edit += other.chunk_edit;
+        self.chunk_fill += other.chunk_fill;
+        self.total += other.total;
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StemGas {
+    #[serde(serialize_with = "serialize_hex")]
+    pub stem: [u8; 31],
+    pub keys: usize,
+    pub written: usize,
+    #[serde(flatten)]
+    pub cost: GasCost,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GasReport {
+    pub schedule: GasSchedule,
+    #[serde(flatten)]
+    pub cost: GasCost,
+    pub stems: Vec<StemGas>,
+    /// Whether the post-state of the block was available to find the written keys
+    pub post_state: bool,
+    /// Keys of the witness without a post-state value. Their writes aren't charged,
+    /// so the write costs are a lower bound unless this is 0.
+    pub missing_post_values: usize,
+}
+
+impl GasReport {
+    /// Total cost of every stem, used for coloring the graph.
+    pub fn stem_totals(&self) -> BTreeMap<[u8; 31], u64> {
+        self.stems.iter().map(|s| (s.stem, s.cost.total)).collect()
+    }
+}
+
+/// Computes the stateless gas of a block witness.
+///
+/// `post_values` holds the values of keys after the block was applied. Keys
+/// missing from it are considered untouched and counted in
+/// `missing_post_values`, so without it only the read costs are charged.
+pub fn witness_gas(
+    hint: &UpdateHint,
+    keyvals: &KeyVals,
+    post_values: Option<&BTreeMap<[u8; 32], Option<[u8; 32]>>>,
+    schedule: &GasSchedule,
+) -> GasReport {
+    // (keys, written keys, filled keys) by stem
+    let mut accesses = BTreeMap::<[u8; 31], (usize, usize, usize)>::new();
+    let mut missing_post_values = 0;
+
+    for stem in hint.depths_and_ext_by_stem.keys() {
+        accesses.insert(*stem, (0, 0, 0));
+    }
+
+    for (indx, key) in keyvals.keys.iter().enumerate() {
+        let mut stem = [0u8; 31];
+        stem.copy_from_slice(&key[..31]);
+        let entry = accesses.entry(stem).or_default();
+        entry.0 += 1;
+
+        let pre = keyvals.values[indx];
+        match post_values.and_then(|values| values.get(key)) {
+            Some(post) if *post != pre => {
+                entry.1 += 1;
+                if pre.is_none() {
+                    entry.2 += 1;
+                }
+            }
+            Some(_) => {}
+            None => missing_post_values += 1,
+        }
+    }
+
+    let mut total = GasCost::default();
+    let stems = accesses
+        .into_iter()
+        .map(|(stem, (keys, written, filled))| {
+            let mut cost = GasCost {
+                branch_read: schedule.witness_branch_cost,
+                chunk_read: schedule.witness_chunk_cost * keys as u64,
+                subtree_edit: 0,
+                chunk_edit: schedule.chunk_edit_cost * written as u64,
+                chunk_fill: schedule.chunk_fill_cost * filled as u64,
+                total: 0,
+            };
+            if written > 0 {
+                cost.subtree_edit = schedule.subtree_edit_cost;
+            }
+            cost.total = cost.branch_read
+                + cost.chunk_read
+                + cost.subtree_edit
+                + cost.chunk_edit
+                + cost.chunk_fill;
+            total.add(&cost);
+
+            StemGas {
+                stem,
+                keys,
+                written,
+                cost,
+            }
+        })
+        .collect();
+
+    GasReport {
+        schedule: *schedule,
+        cost: total,
+        stems,
+        post_state: post_values.is_some(),
+        missing_post_values,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::witness;
+
+    #[test]
+    fn test_read_costs() {
+        let mut second = [0u8; 32];
+        second[31] = 1;
+        let mut other_stem = [0u8; 32];
+        other_stem[0] = 1;
+        let (hint, keyvals) = witness(&[[0u8; 32], second, other_stem], &[]);
+
+        let report = witness_gas(&hint, &keyvals, None, &GasSchedule::default());
+
+        assert_eq!(report.stems.len(), 2);
+        assert_eq!(report.cost.branch_read, 2 * 1900);
+        assert_eq!(report.cost.chunk_read, 3 * 200);
+        assert_eq!(report.cost.total, 2 * 1900 + 3 * 200);
+        assert!(!report.post_state);
+        assert_eq!(report.missing_post_values, 3);
+    }
+
+    #[test]
+    fn test_write_costs() {
+        let mut absent = [0u8; 32];
+        absent[31] = 1;
+        let mut untouched = [0u8; 32];
+        untouched[31] = 2;
+        let (hint, keyvals) = witness(&[[0u8; 32]], &[absent, untouched]);
+
+        let post_values = BTreeMap::from([([0u8; 32], Some([2u8; 32])), (absent, Some([3u8; 32]))]);
+        let report = witness_gas(&hint, &keyvals, Some(&post_values), &GasSchedule::default());
+
+        assert_eq!(report.stems.len(), 1);
+        assert_eq!(report.stems[0].written, 2);
+        assert_eq!(report.cost.subtree_edit, 3000);
+        assert_eq!(report.cost.chunk_edit, 2 * 500);
+        assert_eq!(report.cost.chunk_fill, 6200);
+        assert!(report.post_state);
+        assert_eq!(report.missing_post_values, 1);
+    }
+}
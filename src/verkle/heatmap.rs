@@ -0,0 +1,59 @@
+use crate::types::serialize_hex;
+use block_verkle_proof_extractor::keyvals::KeyVals;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+pub struct StemHeat {
+    #[serde(serialize_with = "serialize_hex")]
+    pub stem: [u8; 31],
+    /// Number of blocks touching the stem
+    pub blocks: usize,
+    /// Keys of the stem in the witness of every block, in the order of `StemHeatmap::blocks`
+    pub keys: Vec<usize>,
+}
+
+/// Stems touched per block over a block range, hottest stems first
+#[derive(Debug, Serialize)]
+pub struct StemHeatmap {
+    pub from: u64,
+    pub to: u64,
+    pub blocks: Vec<u64>,
+    pub skipped: Vec<u64>,
+    pub stems: Vec<StemHeat>,
+}
+
+pub fn stem_heatmap(
+    from: u64,
+    to: u64,
+    blocks: &[(u64, &KeyVals)],
+    skipped: Vec<u64>,
+) -> StemHeatmap {
+    let mut keys = BTreeMap::<[u8; 31], Vec<usize>>::new();
+    for (column, (_, keyvals)) in blocks.iter().enumerate() {
+        for key in keyvals.keys.iter() {
+            let mut stem = [0u8; 31];
+            stem.copy_from_slice(&key[..31]);
+            keys.entry(stem).or_insert_with(|| vec![0; blocks.len()])[column] += 1;
+        }
+    }
+
+    let mut stems: Vec<_> = keys
+        .into_iter()
+        .map(|(stem, keys)| StemHeat {
+            stem,
+            blocks: keys.iter().filter(|count| **count > 0).count(),
+            keys,
+        })
+        .collect();
+    // stable, so stems touched as often stay sorted
+    stems.sort_by_key(|stem| std::cmp::Reverse(stem.blocks));
+
+    StemHeatmap {
+        from,
+        to,
+        blocks: blocks.iter().map(|(number, _)| *number).collect(),
+        skipped,
+        stems,
+    }
+}
@@ -1,6 +1,24 @@
+mod address;
 mod algorithm;
+mod explain;
+mod gas;
+mod heatmap;
+mod mpt;
 mod proof;
+mod stats;
+mod suffix;
+#[cfg(test)]
+pub(crate) mod testing;
+mod tree;
 // mod keyvals;
 
 // pub(crate) use keyvals::KeyVals;
+pub use address::header_stem;
 pub(crate) use algorithm::{decode_block, get_rlp, verification};
+pub use explain::{explain_key, CommitmentKind, ExtensionStatus, KeyProof, PathCommitment};
+pub use gas::{witness_gas, GasCost, GasReport, GasSchedule, StemGas};
+pub use heatmap::{stem_heatmap, StemHeat, StemHeatmap};
+pub use mpt::{mpt_estimate, MptEstimate, MptModel};
+pub use stats::{proof_stats, range_stats, BranchingFill, ExtensionCounts, ProofStats, RangeStats};
+pub use suffix::LeafMeaning;
+pub use tree::{commitment_to_hex, NodeId, NodeKind, TreeNode, WitnessTree};
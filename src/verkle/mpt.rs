@@ -0,0 +1,146 @@
+// Estimate of the witness a hexary Merkle Patricia Trie would need for the same accesses.
+// Every stem stands for one MPT leaf (an account or a storage slot), and it sits as deep
+// in the MPT as it needs to be to get apart from its neighbours: the same number of key
+// bits as its verkle depth, split into `log2(width)` bits per level.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use verkle_trie::proof::UpdateHint;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MptModel {
+    /// Children of a branch node, must be a power of two
+    pub width: u32,
+    /// Size of a child reference
+    pub hash_size: usize,
+    /// Share of the non-empty children in a branch node, empty ones take one byte
+    pub branch_fill: f64,
+    /// Size of a leaf node: remaining key, value and RLP overhead
+    pub leaf_size: usize,
+}
+
+impl Default for MptModel {
+    fn default() -> Self {
+        Self {
+            width: 16,
+            hash_size: 32,
+            branch_fill: 1.0,
+            leaf_size: 110,
+        }
+    }
+}
+
+impl MptModel {
+    fn bits_per_level(&self) -> Result<u32, anyhow::Error> {
+        match self.width.is_power_of_two() && self.width > 1 {
+            true => Ok(self.width.trailing_zeros()),
+            false => Err(anyhow::anyhow!(
+                "MPT width must be a power of two, got {}",
+                self.width
+            )),
+        }
+    }
+
+    /// Checks the model once when the config is loaded, so that estimates don't fail per request
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        self.bits_per_level().map(|_| ())
+    }
+
+    /// Serialized size of a single branch node
+    pub fn branch_size(&self) -> usize {
+        let fill = self.branch_fill.clamp(0.0, 1.0);
+        let children = self.width as f64 * (fill * self.hash_size as f64 + (1.0 - fill));
+        // list header and the empty value slot
+        children.ceil() as usize + 4
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MptEstimate {
+    pub model: MptModel,
+    pub branch_nodes: usize,
+    pub leaves: usize,
+    pub size: usize,
+    /// MPT witness size divided by the verkle proof size
+    pub ratio: f64,
+}
+
+// First `bits` bits of the stem, the rest of the last byte is zeroed
+fn bit_prefix(stem: &[u8; 31], bits: usize) -> Vec<u8> {
+    let mut prefix = stem[..bits.div_ceil(8)].to_vec();
+    if !bits.is_multiple_of(8) {
+        if let Some(last) = prefix.last_mut() {
+            *last &= 0xffu8 << (8 - bits % 8);
+        }
+    }
+    prefix
+}
+
+pub fn mpt_estimate(
+    hint: &UpdateHint,
+    proof_size: usize,
+    model: &MptModel,
+) -> Result<MptEstimate, anyhow::Error> {
+    let bits_per_level = model.bits_per_level()? as usize;
+    // branch nodes are identified by their level and key prefix
+    let mut branch_nodes = BTreeSet::<(usize, Vec<u8>)>::new();
+
+    for (stem, (_, depth)) in hint.depths_and_ext_by_stem.iter() {
+        let levels = (*depth as usize * 8).div_ceil(bits_per_level);
+        for level in 0..levels {
+            branch_nodes.insert((level, bit_prefix(stem, level * bits_per_level)));
+        }
+    }
+
+    let leaves = hint.depths_and_ext_by_stem.len();
+    let size = branch_nodes.len() * model.branch_size() + leaves * model.leaf_size;
+    let ratio = match proof_size {
+        0 => 0.0,
+        _ => size as f64 / proof_size as f64,
+    };
+
+    Ok(MptEstimate {
+        model: *model,
+        branch_nodes: branch_nodes.len(),
+        leaves,
+        size,
+        ratio,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_prefix() {
+        let mut stem = [0u8; 31];
+        stem[0] = 0xab;
+        stem[1] = 0xcd;
+
+        assert_eq!(bit_prefix(&stem, 0), Vec::<u8>::new());
+        assert_eq!(bit_prefix(&stem, 4), vec![0xa0]);
+        assert_eq!(bit_prefix(&stem, 8), vec![0xab]);
+        assert_eq!(bit_prefix(&stem, 12), vec![0xab, 0xc0]);
+    }
+
+    #[test]
+    fn test_branch_size() {
+        let full = MptModel::default();
+        assert_eq!(full.branch_size(), 16 * 32 + 4);
+
+        let empty = MptModel {
+            branch_fill: 0.0,
+            ..MptModel::default()
+        };
+        assert_eq!(empty.branch_size(), 16 + 4);
+
+        let invalid = MptModel {
+            width: 10,
+            ..MptModel::default()
+        };
+        assert!(invalid.bits_per_level().is_err());
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -7,6 +7,8 @@ use verkle_trie::proof::VerkleProof;
 #[allow(dead_code)]
 pub(crate) struct Proof {
     pub verkle_proof: VerkleProof,
+    /// Length of the serialized proof in bytes
+    pub serialized_size: usize,
 }
 
 impl Decodable for Proof {
@@ -16,6 +18,7 @@ impl Decodable for Proof {
 
         Ok(Proof {
             verkle_proof: proof,
+            serialized_size: serialized_proof.len(),
         })
     }
 }
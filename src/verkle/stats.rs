@@ -0,0 +1,250 @@
+use crate::verkle::{
+    mpt::{mpt_estimate, MptEstimate, MptModel},
+    NodeKind, WitnessTree,
+};
+use block_verkle_proof_extractor::keyvals::KeyVals;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use verkle_trie::proof::{ExtPresent, UpdateHint};
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct ExtensionCounts {
+    pub present: usize,
+    pub different_stem: usize,
+    pub none: usize,
+}
+
+impl ExtensionCounts {
+    fn add(&mut self, other: &ExtensionCounts) {
+        self.present += other.present;
+        self.different_stem += other.different_stem;
+        self.none += other.none;
+    }
+}
+
+/// How full the internal nodes of the witness are
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct BranchingFill {
+    pub internal_nodes: usize,
+    pub children: usize,
+    pub average_children: f64,
+    /// Share of the 256 child slots used by the witness
+    pub fill: f64,
+}
+
+impl BranchingFill {
+    fn new(internal_nodes: usize, children: usize) -> Self {
+        let average_children = match internal_nodes {
+            0 => 0.0,
+            _ => children as f64 / internal_nodes as f64,
+        };
+
+        Self {
+            internal_nodes,
+            children,
+            average_children,
+            fill: average_children / 256.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProofStats {
+    pub block_number: u64,
+    pub keys: usize,
+    pub stems: usize,
+    pub proof_size: usize,
+    pub bytes_per_key: f64,
+    pub commitments: usize,
+    /// Number of stems by their depth
+    pub depths: BTreeMap<u8, usize>,
+    pub extension: ExtensionCounts,
+    pub other_stems: usize,
+    pub branching: BranchingFill,
+    /// Witness size of a Merkle Patricia Trie for the same keys
+    pub mpt: MptEstimate,
+}
+
+/// Sums of the per-block statistics over a range of blocks
+#[derive(Debug, Serialize)]
+pub struct RangeStats {
+    pub from: u64,
+    pub to: u64,
+    pub blocks: usize,
+    /// Blocks that could not be fetched or verified
+    pub skipped: Vec<u64>,
+    pub keys: usize,
+    pub stems: usize,
+    pub proof_size: usize,
+    pub bytes_per_key: f64,
+    pub commitments: usize,
+    pub depths: BTreeMap<u8, usize>,
+    pub extension: ExtensionCounts,
+    pub other_stems: usize,
+    pub branching: BranchingFill,
+    pub mpt_size: usize,
+    pub mpt_ratio: f64,
+}
+
+fn div(a: usize, b: usize) -> f64 {
+    match b {
+        0 => 0.0,
+        _ => a as f64 / b as f64,
+    }
+}
+
+pub fn proof_stats(
+    block_number: u64,
+    proof_size: usize,
+    hint: &UpdateHint,
+    keyvals: &KeyVals,
+    tree: &WitnessTree,
+    mpt_model: &MptModel,
+) -> Result<ProofStats, anyhow::Error> {
+    let mut depths = BTreeMap::new();
+    let mut extension = ExtensionCounts::default();
+
+    for (ext, depth) in hint.depths_and_ext_by_stem.values() {
+        *depths.entry(*depth).or_default() += 1;
+        match ext {
+            ExtPresent::Present => extension.present += 1,
+            ExtPresent::DifferentStem => extension.different_stem += 1,
+            ExtPresent::None => extension.none += 1,
+        }
+    }
+
+    let internal = tree
+        .nodes()
+        .iter()
+        .filter(|node| node.kind == NodeKind::Internal);
+    let (mut internal_nodes, mut used_slots) = (0, 0);
+    for node in internal {
+        internal_nodes += 1;
+        // empty slots prove absence, they are not children
+        used_slots += node
+            .children
+            .iter()
+            .filter(|child| tree.node(**child).kind != NodeKind::Empty)
+            .count();
+    }
+    let mpt = mpt_estimate(hint, proof_size, mpt_model)?;
+
+    Ok(ProofStats {
+        block_number,
+        keys: keyvals.keys.len(),
+        stems: hint.depths_and_ext_by_stem.len(),
+        proof_size,
+        bytes_per_key: div(proof_size, keyvals.keys.len()),
+        commitments: hint.commitments_by_path.len(),
+        depths,
+        extension,
+        other_stems: hint.other_stems_by_prefix.len(),
+        branching: BranchingFill::new(internal_nodes, used_slots),
+        mpt,
+    })
+}
+
+pub fn range_stats(from: u64, to: u64, blocks: &[ProofStats], skipped: Vec<u64>) -> RangeStats {
+    let mut depths = BTreeMap::new();
+    let mut extension = ExtensionCounts::default();
+    let (mut internal_nodes, mut children) = (0, 0);
+
+    for stats in blocks {
+        for (depth, count) in stats.depths.iter() {
+            *depths.entry(*depth).or_default() += count;
+        }
+        extension.add(&stats.extension);
+        internal_nodes += stats.branching.internal_nodes;
+        children += stats.branching.children;
+    }
+
+    let keys = blocks.iter().map(|s| s.keys).sum();
+    let proof_size = blocks.iter().map(|s| s.proof_size).sum();
+    let mpt_size = blocks.iter().map(|s| s.mpt.size).sum();
+
+    RangeStats {
+        from,
+        to,
+        blocks: blocks.len(),
+        skipped,
+        keys,
+        stems: blocks.iter().map(|s| s.stems).sum(),
+        proof_size,
+        bytes_per_key: div(proof_size, keys),
+        commitments: blocks.iter().map(|s| s.commitments).sum(),
+        depths,
+        extension,
+        other_stems: blocks.iter().map(|s| s.other_stems).sum(),
+        branching: BranchingFill::new(internal_nodes, children),
+        mpt_size,
+        mpt_ratio: div(mpt_size, proof_size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::witness;
+
+    fn key(prefix: &[u8], suffix: u8) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key[..prefix.len()].copy_from_slice(prefix);
+        key[31] = suffix;
+        key
+    }
+
+    fn stats(block_number: u64) -> ProofStats {
+        // stems 00.. and 0005.. split below the 00 slot of the root, 0101.. is alone in its slot
+        let present = [key(&[], 0), key(&[], 1), key(&[0, 5], 0), [1u8; 32]];
+        // 0001.. lands in an empty slot of the 00 node, 0102.. on the extension of 0101..
+        // and 02.. in an empty slot of the root
+        let absent = [key(&[0, 1], 0), key(&[1, 2], 0), key(&[2], 0)];
+        let (hint, keyvals) = witness(&present, &absent);
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+
+        proof_stats(
+            block_number,
+            700,
+            &hint,
+            &keyvals,
+            &tree,
+            &MptModel::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_proof_stats() {
+        let stats = stats(5);
+
+        assert_eq!(stats.keys, 7);
+        assert_eq!(stats.stems, 6);
+        assert_eq!(stats.bytes_per_key, 100.0);
+        assert_eq!(stats.depths, BTreeMap::from([(1, 3), (2, 3)]));
+        assert_eq!(stats.extension.present, 3);
+        assert_eq!(stats.extension.different_stem, 1);
+        assert_eq!(stats.extension.none, 2);
+        assert_eq!(stats.other_stems, 1);
+        // the root and the 00 node, each with two extensions or internal children
+        assert_eq!(stats.branching.internal_nodes, 2);
+        assert_eq!(stats.branching.children, 4);
+    }
+
+    #[test]
+    fn test_range_stats() {
+        let blocks = [stats(5), stats(6)];
+        let range = range_stats(5, 7, &blocks, vec![7]);
+
+        assert_eq!(range.blocks, 2);
+        assert_eq!(range.skipped, vec![7]);
+        assert_eq!(range.keys, 14);
+        assert_eq!(range.stems, 12);
+        assert_eq!(range.proof_size, 1400);
+        assert_eq!(range.bytes_per_key, 100.0);
+        assert_eq!(range.depths, BTreeMap::from([(1, 6), (2, 6)]));
+        assert_eq!(range.extension.none, 4);
+        assert_eq!(range.branching.average_children, 2.0);
+        assert_eq!(range.mpt_size, 2 * blocks[0].mpt.size);
+        assert_eq!(range.mpt_ratio, blocks[0].mpt.ratio);
+    }
+}
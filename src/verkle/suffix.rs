@@ -0,0 +1,64 @@
+use std::fmt::{Display, Formatter};
+
+const HEADER_STORAGE_OFFSET: u8 = 64;
+const CODE_OFFSET: u8 = 128;
+
+/// What a leaf holds by the last byte of its key, as laid out by
+/// [EIP-6800](https://eips.ethereum.org/EIPS/eip-6800) for the stem of an account header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeafMeaning {
+    Version,
+    Balance,
+    Nonce,
+    CodeHash,
+    CodeSize,
+    /// One of the first 64 storage slots
+    Storage(u8),
+    /// One of the first 128 code chunks
+    Code(u8),
+    Reserved(u8),
+}
+
+impl LeafMeaning {
+    /// Only holds for a key whose stem is the header stem of an account
+    pub fn of(key: &[u8; 32]) -> Self {
+        match key[31] {
+            0 => LeafMeaning::Version,
+            1 => LeafMeaning::Balance,
+            2 => LeafMeaning::Nonce,
+            3 => LeafMeaning::CodeHash,
+            4 => LeafMeaning::CodeSize,
+            suffix if suffix >= CODE_OFFSET => LeafMeaning::Code(suffix - CODE_OFFSET),
+            suffix if suffix >= HEADER_STORAGE_OFFSET => {
+                LeafMeaning::Storage(suffix - HEADER_STORAGE_OFFSET)
+            }
+            suffix => LeafMeaning::Reserved(suffix),
+        }
+    }
+
+    /// Whether the value is a little-endian integer rather than a hash or bytes
+    pub fn is_number(self) -> bool {
+        matches!(
+            self,
+            LeafMeaning::Version
+                | LeafMeaning::Balance
+                | LeafMeaning::Nonce
+                | LeafMeaning::CodeSize
+        )
+    }
+}
+
+impl Display for LeafMeaning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeafMeaning::Version => write!(f, "version"),
+            LeafMeaning::Balance => write!(f, "balance"),
+            LeafMeaning::Nonce => write!(f, "nonce"),
+            LeafMeaning::CodeHash => write!(f, "code hash"),
+            LeafMeaning::CodeSize => write!(f, "code size"),
+            LeafMeaning::Storage(slot) => write!(f, "storage slot {slot}"),
+            LeafMeaning::Code(chunk) => write!(f, "code chunk {chunk}"),
+            LeafMeaning::Reserved(suffix) => write!(f, "suffix {suffix}"),
+        }
+    }
+}
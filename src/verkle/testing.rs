@@ -0,0 +1,41 @@
+// Helpers for building real witnesses in tests
+
+use block_verkle_proof_extractor::keyvals::KeyVals;
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+use verkle_trie::{database::memory_db::MemoryDb, proof::UpdateHint, TestConfig, Trie, TrieTrait};
+
+/// Builds a trie holding the `present` keys and proves `present` and `absent` keys against it
+pub fn witness(present: &[[u8; 32]], absent: &[[u8; 32]]) -> (UpdateHint, KeyVals) {
+    let mut trie = Trie::new(TestConfig::new(MemoryDb::new()));
+    for key in present {
+        trie.insert_single(*key, [1u8; 32]);
+    }
+
+    let keys: Vec<[u8; 32]> = present.iter().chain(absent).copied().collect();
+    let values: Vec<Option<[u8; 32]>> = keys.iter().map(|key| trie.get(*key)).collect();
+    let proof = trie.create_verkle_proof(keys.clone().into_iter());
+    let (checked, hint) = proof.check(keys.clone(), values.clone(), trie.root_commitment());
+    assert!(checked);
+
+    (hint.expect("update hint"), KeyVals { keys, values })
+}
+
+/// Distinct keys sharing few leading bytes, so that random sets build deep trees
+pub fn keys() -> impl Strategy<Value = Vec<[u8; 32]>> {
+    proptest::collection::vec((0u8..3, 0u8..3, 0u8..3, any::<u8>()), 1..40).prop_map(|parts| {
+        parts
+            .into_iter()
+            .map(|(a, b, c, suffix)| {
+                let mut key = [0u8; 32];
+                key[0] = a;
+                key[1] = b;
+                key[30] = c;
+                key[31] = suffix;
+                key
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    })
+}
@@ -0,0 +1,428 @@
+use crate::types::serialize_hex;
+use ark_serialize::CanonicalSerialize;
+use block_verkle_proof_extractor::keyvals::KeyVals;
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+use verkle_trie::{
+    proof::{ExtPresent, UpdateHint},
+    Element,
+};
+
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeKind {
+    /// Branch node with up to 256 children
+    Internal,
+    /// Extension node of a stem present in the tree
+    Extension {
+        #[serde(serialize_with = "serialize_hex")]
+        stem: [u8; 31],
+    },
+    /// Extension of another stem, found where a requested stem would be
+    OtherStem {
+        #[serde(serialize_with = "serialize_hex")]
+        stem: [u8; 31],
+    },
+    /// Empty child slot of an internal node, proves the absence of a stem
+    Empty,
+    /// C1 (suffixes 0..128) or C2 (suffixes 128..256) commitment of an extension
+    Suffix { index: u8 },
+    Leaf {
+        #[serde(serialize_with = "serialize_hex")]
+        key: [u8; 32],
+        #[serde(serialize_with = "serialize_hex")]
+        value: [u8; 32],
+    },
+    /// Requested key without a value
+    Absent {
+        #[serde(serialize_with = "serialize_hex")]
+        key: [u8; 32],
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreeNode {
+    pub kind: NodeKind,
+    /// Tree path of the node as in `UpdateHint::commitments_by_path`: stem bytes down to
+    /// the extension, then the suffix-commitment slot (2 or 3) and the suffix of the key
+    #[serde(serialize_with = "serialize_hex")]
+    pub path: Vec<u8>,
+    #[serde(serialize_with = "serialize_commitment")]
+    pub commitment: Option<Element>,
+    pub parent: Option<NodeId>,
+    pub children: Vec<NodeId>,
+}
+
+/// The part of the verkle tree revealed by a block witness.
+/// A parent always precedes its children in `nodes`, the root is the first node.
+#[derive(Debug, Serialize)]
+pub struct WitnessTree {
+    nodes: Vec<TreeNode>,
+    #[serde(skip)]
+    by_path: HashMap<Vec<u8>, NodeId>,
+    /// Leaves and absent leaves by their key
+    #[serde(skip)]
+    by_key: HashMap<[u8; 32], NodeId>,
+}
+
+pub fn commitment_to_hex(comm: &Element) -> String {
+    let mut bytes = vec![];
+    let _res = CanonicalSerialize::serialize(comm, &mut bytes);
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn serialize_commitment<S>(comm: &Option<Element>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match comm {
+        Some(comm) => serializer.serialize_str(&commitment_to_hex(comm)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn stem_of(key: &[u8; 32]) -> [u8; 31] {
+    let mut stem = [0u8; 31];
+    stem.copy_from_slice(&key[..31]);
+    stem
+}
+
+impl TreeNode {
+    /// Key of a leaf or an absent leaf
+    pub fn key(&self) -> Option<&[u8; 32]> {
+        match &self.kind {
+            NodeKind::Leaf { key, .. } | NodeKind::Absent { key } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Label of the edge from the parent: the child index, `c_1`/`c_2` for suffix commitments
+    pub fn edge_label(&self) -> String {
+        match self.kind {
+            NodeKind::Suffix { index } => format!("c_{index}"),
+            _ => match self.path.last() {
+                Some(index) => format!("{:0x}", index),
+                None => "".to_owned(),
+            },
+        }
+    }
+}
+
+impl WitnessTree {
+    pub fn from_hint(hint: &UpdateHint, keyvals: &KeyVals) -> Self {
+        let mut tree = WitnessTree {
+            nodes: Vec::with_capacity(hint.commitments_by_path.len() + keyvals.keys.len()),
+            by_path: HashMap::with_capacity(hint.commitments_by_path.len()),
+            by_key: HashMap::with_capacity(keyvals.keys.len()),
+        };
+
+        let root = tree.push(NodeKind::Internal, vec![], hint, None);
+        tree.by_path.insert(vec![], root);
+
+        for (stem, (ext, depth)) in hint.depths_and_ext_by_stem.iter() {
+            let depth = *depth as usize;
+            // internal nodes down to the extension
+            let mut parent = root;
+            for i in 1..depth {
+                parent = tree.child(NodeKind::Internal, &stem[..i], hint, parent);
+            }
+
+            let kind = match ext {
+                ExtPresent::Present => NodeKind::Extension { stem: *stem },
+                ExtPresent::DifferentStem => match hint.other_stems_by_prefix.get(&stem[..depth]) {
+                    Some(other) => NodeKind::OtherStem { stem: *other },
+                    None => {
+                        tracing::error!("No other stem for {}", hex::encode(stem));
+                        continue;
+                    }
+                },
+                ExtPresent::None => NodeKind::Empty,
+            };
+            let node = tree.child(kind.clone(), &stem[..depth], hint, parent);
+            // a requested stem may be the other stem of another requested one
+            if let NodeKind::Extension { .. } = kind {
+                tree.nodes[node].kind = kind;
+            }
+        }
+
+        for (indx, key) in keyvals.keys.iter().enumerate() {
+            let stem = stem_of(key);
+            let (ext, depth) = match hint.depths_and_ext_by_stem.get(&stem) {
+                Some((ext, depth)) => (*ext, *depth as usize),
+                None => continue,
+            };
+            let ext_node = match tree.by_path.get(&stem[..depth]) {
+                Some(node) => *node,
+                None => continue,
+            };
+
+            match ext {
+                ExtPresent::Present => {
+                    let slot = 2 + key[31] / 128;
+                    let mut path = stem[..depth].to_vec();
+                    path.push(slot);
+                    let suffix_node =
+                        tree.child(NodeKind::Suffix { index: slot - 1 }, &path, hint, ext_node);
+
+                    path.push(key[31]);
+                    let kind = match keyvals.values[indx] {
+                        Some(value) => NodeKind::Leaf { key: *key, value },
+                        None => NodeKind::Absent { key: *key },
+                    };
+                    let leaf = tree.push(kind, path, hint, Some(suffix_node));
+                    tree.by_key.insert(*key, leaf);
+                }
+                ExtPresent::DifferentStem => {
+                    let mut path = stem[..depth].to_vec();
+                    path.push(key[31]);
+                    let leaf =
+                        tree.push(NodeKind::Absent { key: *key }, path, hint, Some(ext_node));
+                    tree.by_key.insert(*key, leaf);
+                }
+                ExtPresent::None => {}
+            }
+        }
+
+        for node in 0..tree.nodes.len() {
+            let mut children = std::mem::take(&mut tree.nodes[node].children);
+            // absent keys of another stem can share a path, they are told apart by the key
+            children.sort_by_key(|child| (&tree.nodes[*child].path, tree.nodes[*child].key()));
+            tree.nodes[node].children = children;
+        }
+        tree.renumber();
+
+        tree
+    }
+
+    // Puts the nodes in preorder, so that ids don't depend on the order of the keys
+    fn renumber(&mut self) {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![self.root()];
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            stack.extend(self.nodes[id].children.iter().rev());
+        }
+        let mut new_ids = vec![0; self.nodes.len()];
+        for (new_id, id) in order.iter().enumerate() {
+            new_ids[*id] = new_id;
+        }
+
+        let mut nodes: Vec<_> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.nodes = order
+            .iter()
+            .filter_map(|id| nodes[*id].take())
+            .map(|mut node| {
+                node.parent = node.parent.map(|parent| new_ids[parent]);
+                for child in node.children.iter_mut() {
+                    *child = new_ids[*child];
+                }
+                node
+            })
+            .collect();
+        for id in self.by_path.values_mut().chain(self.by_key.values_mut()) {
+            *id = new_ids[*id];
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: NodeKind,
+        path: Vec<u8>,
+        hint: &UpdateHint,
+        parent: Option<NodeId>,
+    ) -> NodeId {
+        let commitment = match kind {
+            NodeKind::Leaf { .. } | NodeKind::Absent { .. } | NodeKind::Empty => None,
+            _ => hint.commitments_by_path.get(&path).copied(),
+        };
+        let id = self.nodes.len();
+        self.nodes.push(TreeNode {
+            kind,
+            path,
+            commitment,
+            parent,
+            children: vec![],
+        });
+        if let Some(parent) = parent {
+            self.nodes[parent].children.push(id);
+        }
+        id
+    }
+
+    // Returns the node at `path`, creating it under `parent` if needed
+    fn child(&mut self, kind: NodeKind, path: &[u8], hint: &UpdateHint, parent: NodeId) -> NodeId {
+        if let Some(id) = self.by_path.get(path) {
+            return *id;
+        }
+        let id = self.push(kind, path.to_vec(), hint, Some(parent));
+        self.by_path.insert(path.to_vec(), id);
+        id
+    }
+
+    pub fn root(&self) -> NodeId {
+        0
+    }
+
+    pub fn node(&self, id: NodeId) -> &TreeNode {
+        &self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> &[TreeNode] {
+        &self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Nodes from the root down to `id`
+    pub fn path_to(&self, id: NodeId) -> Vec<NodeId> {
+        let mut path = vec![id];
+        while let Some(parent) = self.nodes[*path.last().unwrap()].parent {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+
+    /// Node at `path`, leaves are only found by their key
+    pub fn node_at(&self, path: &[u8]) -> Option<NodeId> {
+        self.by_path.get(path).copied()
+    }
+
+    /// The node proving `key`: its leaf, absent leaf or the empty slot of its stem
+    pub fn find_key(&self, key: &[u8; 32]) -> Option<NodeId> {
+        if let Some(id) = self.by_key.get(key) {
+            return Some(*id);
+        }
+        (1..32)
+            .filter_map(|len| self.node_at(&key[..len]))
+            .find(|id| self.nodes[*id].kind == NodeKind::Empty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verkle::testing::{keys, witness};
+    use proptest::{prelude::*, sample::Index};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_tree_structure() {
+        let mut sibling = [0u8; 32];
+        sibling[1] = 1;
+        let mut absent = [0u8; 32];
+        absent[31] = 200;
+        let (hint, keyvals) = witness(&[[0u8; 32], sibling], &[absent]);
+
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+
+        let kinds: Vec<_> = tree
+            .path_to(tree.find_key(&absent).expect("key in tree"))
+            .into_iter()
+            .map(|id| tree.node(id).kind.clone())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                NodeKind::Internal,
+                NodeKind::Internal,
+                NodeKind::Extension { stem: [0u8; 31] },
+                NodeKind::Suffix { index: 2 },
+                NodeKind::Absent { key: absent },
+            ]
+        );
+        for node in tree.nodes() {
+            for child in node.children.iter() {
+                assert!(tree.node(*child).path.starts_with(&node.path));
+            }
+        }
+    }
+
+    #[test]
+    fn test_tree_ignores_key_order() {
+        let mut other = [0u8; 32];
+        other[1] = 1;
+        let (hint, keyvals) = witness(&[[0u8; 32], [1u8; 32], [2u8; 32]], &[other, [5u8; 32]]);
+        let reversed = KeyVals {
+            keys: keyvals.keys.iter().rev().copied().collect(),
+            values: keyvals.values.iter().rev().copied().collect(),
+        };
+
+        let tree = WitnessTree::from_hint(&hint, &keyvals);
+        let other_tree = WitnessTree::from_hint(&hint, &reversed);
+
+        let nodes = |tree: &WitnessTree| {
+            tree.nodes()
+                .iter()
+                .map(|node| {
+                    (
+                        node.kind.clone(),
+                        node.path.clone(),
+                        node.parent,
+                        node.children.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(nodes(&tree), nodes(&other_tree));
+        assert_eq!(tree.find_key(&other), other_tree.find_key(&other));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_tree_matches_trie(keys in keys(), split in any::<Index>()) {
+            let (present, absent) = keys.split_at(split.index(keys.len()) + 1);
+            let stems: BTreeSet<[u8; 31]> = present.iter().map(stem_of).collect();
+            let (hint, keyvals) = witness(present, absent);
+
+            let tree = WitnessTree::from_hint(&hint, &keyvals);
+
+            // number of stems of the trie below `path`
+            let below = |path: &[u8]| stems.iter().filter(|stem| stem.starts_with(path)).count();
+            for node in tree.nodes() {
+                if let Some(parent) = node.parent {
+                    let parent = &tree.node(parent).path;
+                    prop_assert!(parent.len() < node.path.len());
+                    prop_assert!(node.path.starts_with(parent));
+                }
+                match &node.kind {
+                    NodeKind::Internal => {
+                        prop_assert!(node.commitment.is_some());
+                        prop_assert!(node.path.is_empty() || below(&node.path) > 1);
+                    }
+                    NodeKind::Extension { stem } | NodeKind::OtherStem { stem } => {
+                        prop_assert!(node.commitment.is_some());
+                        prop_assert!(stems.contains(stem));
+                        prop_assert!(stem.starts_with(&node.path));
+                        prop_assert_eq!(below(&node.path), 1);
+                    }
+                    NodeKind::Empty => prop_assert_eq!(below(&node.path), 0),
+                    NodeKind::Suffix { .. } => {
+                        let parent = node.parent.map(|parent| &tree.node(parent).kind);
+                        let under_extension = matches!(parent, Some(NodeKind::Extension { .. }));
+                        prop_assert!(under_extension);
+                    }
+                    NodeKind::Leaf { key, value } => {
+                        prop_assert!(present.contains(key));
+                        prop_assert_eq!(*value, [1u8; 32]);
+                    }
+                    NodeKind::Absent { key } => prop_assert!(absent.contains(key)),
+                }
+            }
+            for key in keys.iter() {
+                prop_assert!(tree.find_key(key).is_some());
+            }
+        }
+    }
+}
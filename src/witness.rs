@@ -0,0 +1,51 @@
+use crate::verkle::{decode_block, verification, WitnessTree};
+use block_verkle_proof_extractor::keyvals::KeyVals;
+use verkle_trie::proof::UpdateHint;
+
+pub(crate) struct Witness {
+    pub hint: UpdateHint,
+    pub keyvals: KeyVals,
+    pub tree: WitnessTree,
+    pub proof_size: usize,
+    /// Coinbase and transaction recipients of the block
+    pub addresses: Vec<[u8; 20]>,
+}
+
+/// RLP of a block and of its parent, `0x` prefixed hex
+pub(crate) struct BlockRlp {
+    pub number: u64,
+    pub rlp: String,
+    pub parent_rlp: String,
+}
+
+pub(crate) enum WitnessError {
+    Decode(anyhow::Error),
+    Verification(anyhow::Error),
+}
+
+/// Decodes the block and verifies its proof against the root of the previous block.
+/// Decoding, verification and the tree are CPU-bound, the server runs them on the blocking pool.
+pub(crate) fn verify_witness(
+    block_rlp: String,
+    previous_block_rlp: String,
+) -> Result<Witness, WitnessError> {
+    let block = decode_block(block_rlp).map_err(WitnessError::Decode)?;
+    let previous_block = decode_block(previous_block_rlp).map_err(WitnessError::Decode)?;
+
+    let parent_root = previous_block.header.storage_root;
+    let proof_size = block.header.proof.serialized_size;
+    let addresses = block.addresses();
+    let keyvals = KeyVals {
+        keys: block.header.keyvals.keys.clone(),
+        values: block.header.keyvals.values.clone(),
+    };
+
+    let hint = verification(block, &parent_root).map_err(WitnessError::Verification)?;
+    Ok(Witness {
+        tree: WitnessTree::from_hint(&hint, &keyvals),
+        hint,
+        keyvals,
+        proof_size,
+        addresses,
+    })
+}